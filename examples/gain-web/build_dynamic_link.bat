@@ -1,4 +1,5 @@
 cargo build --target wasm32-unknown-unknown --features build_processor
 wasm-bindgen target/wasm32-unknown-unknown/debug/gain_web.wasm --target web --out-dir web_dynamic --out-name gain --keep-debug --no-typescript
 cargo build --target wasm32-unknown-unknown
-wasm-bindgen target/wasm32-unknown-unknown/debug/gain_web.wasm --target web --out-dir web_dynamic --out-name gain_loader --keep-debug
\ No newline at end of file
+wasm-bindgen target/wasm32-unknown-unknown/debug/gain_web.wasm --target web --out-dir web_dynamic --out-name gain_loader --keep-debug
+cargo run --bin typescript_definitions --features typescript_definitions -- web_dynamic gain_loader
\ No newline at end of file
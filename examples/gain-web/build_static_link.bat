@@ -0,0 +1,3 @@
+cargo build --target wasm32-unknown-unknown --features static_linking
+wasm-bindgen target/wasm32-unknown-unknown/debug/gain_web.wasm --target web --out-dir web_static --out-name gain --keep-debug
+cargo run --bin typescript_definitions --features typescript_definitions -- web_static gain
\ No newline at end of file
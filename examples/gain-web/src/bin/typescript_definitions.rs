@@ -0,0 +1,31 @@
+//! Writes the parameter accessors of `GainAudioWorkletNode` next to the wasm-bindgen typings, see the build scripts.
+//!
+//! `cargo run --bin typescript_definitions --features typescript_definitions -- <out-dir> <out-name>`
+use std::{env, fs, path::Path, process::ExitCode};
+
+// the exports of the library, which as a cdylib can't be linked
+#[allow(dead_code)]
+#[path = "../lib.rs"]
+mod exports;
+
+fn main() -> ExitCode {
+    let [_, out_dir, out_name] = &env::args().collect::<Vec<_>>()[..] else {
+        eprintln!("usage: typescript_definitions <out-dir> <out-name>, e.g. web_dynamic gain_loader");
+        return ExitCode::FAILURE;
+    };
+    let definitions = match exports::typescript_definitions(&format!("./{out_name}")) {
+        Ok(definitions) => definitions,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let path = Path::new(out_dir).join(format!("{out_name}.params.d.ts"));
+    match fs::write(&path, definitions) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("can't write {}: {error}", path.display());
+            ExitCode::FAILURE
+        }
+    }
+}
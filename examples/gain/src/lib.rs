@@ -1,12 +1,12 @@
 use std::ops::Mul;
-use pure_audio::{EffectAudioData, InputBuffer, OutputBuffer, ParameterAutomationRate, ParameterDescriptor, ProcessorParameter};
+use pure_audio::{AudioRate, EffectAudioData, InputBuffer, OutputBuffer, ParameterAutomationRate, ParameterDescriptor, ProcessorParameter};
 
 #[derive(Copy, Clone)]
 pub struct GainVolumeParameter(f32);
 
 impl ProcessorParameter for GainVolumeParameter {
     const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
-        automation_rate: ParameterAutomationRate::K,
+        automation_rate: ParameterAutomationRate::A,
         default_value: 1.0,
         max_value: 1.0,
         min_value: 0.0,
@@ -57,9 +57,9 @@ pub fn process(
         outputs: OutputBuffer([[output]]),
         ..
     }: EffectAudioData,
-    volume: GainVolumeParameter,
+    volume: AudioRate<GainVolumeParameter>,
 ) {
-    for (input_sample, output_sample) in input.iter().zip(output) {
+    for ((input_sample, output_sample), volume) in input.iter().zip(output).zip(volume.iter()) {
         *output_sample = input_sample * volume;
     }
 }
\ No newline at end of file
@@ -0,0 +1,18 @@
+use pure_audio::{render, ParameterChange};
+
+#[test]
+fn applies_volume_per_sample() {
+    let input = vec![1.0; 300];
+    let [[output]] = render(gain::process, 48000.0, 300, &[[&input]], &[ParameterChange { frame: 200, index: 0, value: 0.25 }], &[]);
+
+    assert!(output[..200].iter().all(|&sample| sample == 1.0));
+    assert!(output[200..].iter().all(|&sample| sample == 0.25));
+}
+
+#[test]
+fn clamps_volume_to_range() {
+    let input = vec![0.5; 128];
+    let [[output]] = render(gain::process, 48000.0, 128, &[[&input]], &[ParameterChange { frame: 0, index: 0, value: 4.0 }], &[]);
+
+    assert!(output.iter().all(|&sample| sample == 0.5));
+}
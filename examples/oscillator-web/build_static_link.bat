@@ -0,0 +1,2 @@
+cargo build --target wasm32-unknown-unknown --features static_linking
+wasm-bindgen target/wasm32-unknown-unknown/debug/oscillator_web.wasm --target web --out-dir web_static --out-name oscillator --keep-debug
\ No newline at end of file
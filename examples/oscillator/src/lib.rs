@@ -1,5 +1,8 @@
 use std::{collections::HashMap, f32::consts::TAU};
-use pure_audio::{InstrumentAudioData, OutputBuffer};
+use pure_audio::{Command, Event, EventKind, InstrumentAudioData, Notification, OutputBuffer};
+
+// semitones at full pitch bend deflection
+const PITCH_BEND_RANGE: f32 = 2.0;
 
 struct Voice {
     phase: u32,
@@ -9,7 +12,8 @@ struct Voice {
 #[derive(Default)]
 pub struct OscillatorState {
     active: bool,
-    voices: HashMap<u8, Voice>
+    voices: HashMap<u8, Voice>,
+    pitch_bend: f32 // semitones
 }
 
 pub fn process(
@@ -17,35 +21,55 @@ pub fn process(
         events,
         outputs: OutputBuffer([[output]]),
         sample_rate,
-        state: OscillatorState { active, voices },
+        state: OscillatorState { active, voices, pitch_bend },
+        mut messages,
     }: InstrumentAudioData<1, 1, 128, OscillatorState>
 ) {
-    for event in events {
-        match event {
-            pure_audio::Event::NoteOn { key, velocity } => {
-                *active = true;
-                voices.insert(*key, Voice { phase: 0, velocity: *velocity });
-            },
-            pure_audio::Event::NoteOff { key, .. } => {
-                voices.remove(key);
-                *active = voices.len() > 0;
-            },
-        }
+    if messages.commands.contains(&Command::Reset) {
+        voices.clear();
+        *active = false;
+        *pitch_bend = 0.0;
     }
+    let voice_count = voices.len();
 
-    if !*active {
-        return;
-    }
+    // events are sorted by offset
+    let mut events = events.iter().peekable();
+
+    for (i, sample) in output.iter_mut().enumerate() {
+        while let Some(Event { kind, .. }) = events.next_if(|event| event.offset <= i) {
+            match kind {
+                EventKind::NoteOn { key, velocity } => {
+                    *active = true;
+                    voices.insert(*key, Voice { phase: 0, velocity: *velocity });
+                },
+                EventKind::NoteOff { key, .. } => {
+                    voices.remove(key);
+                    *active = !voices.is_empty();
+                },
+                EventKind::PitchBend { value } => {
+                    let deflection = (*value as f32 - EventKind::PITCH_BEND_CENTER as f32) / EventKind::PITCH_BEND_CENTER as f32;
+                    *pitch_bend = deflection * PITCH_BEND_RANGE;
+                },
+                _ => {}
+            }
+        }
+
+        if !*active {
+            continue;
+        }
 
-    for sample in output {
         let mut sum = 0.0;
         let gain_per_voice = 1.0 / voices.len() as f32;
         for (key, Voice { phase, velocity }) in voices.iter_mut() {
-            let freq = 440.0 * 2f32.powf((*key as f32 - 57.0) / 12.0);
+            let freq = 440.0 * 2f32.powf((*key as f32 + *pitch_bend - 57.0) / 12.0);
             let velocity_gain = *velocity as f32 / 127.0;
             *phase = phase.wrapping_add((freq / sample_rate * 10000.0) as u32);
             sum += (TAU * *phase as f32 / 10000.0).sin() * velocity_gain * gain_per_voice;
         }
         *sample = sum;
     }
+
+    if voices.len() != voice_count {
+        messages.notify(Notification::VoiceCount(voices.len()));
+    }
 }
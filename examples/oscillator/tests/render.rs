@@ -0,0 +1,58 @@
+use pure_audio::{render, EventKind, TimedEvent};
+use std::io::Cursor;
+
+fn zero_crossings(samples: &[f32]) -> usize {
+    samples.windows(2).filter(|pair| pair[0].signum() != pair[1].signum()).count()
+}
+
+#[test]
+fn starts_and_stops_on_exact_frames() {
+    let events = [
+        TimedEvent::new(300, 0, EventKind::NoteOn { key: 69, velocity: 127 }),
+        TimedEvent::new(1000, 0, EventKind::NoteOff { key: 69, velocity: 0 }),
+    ];
+    let [[output]] = render(oscillator::process, 48000.0, 1200, &[], &[], &events);
+
+    assert!(output[..300].iter().all(|&sample| sample == 0.0));
+    assert!(output[300..1000].iter().any(|&sample| sample.abs() > 0.5));
+    assert!(output[1000..].iter().all(|&sample| sample == 0.0));
+}
+
+#[test]
+fn renders_midi_file() {
+    // format 1 at 96 ticks per quarter, 60 bpm: key 57 (440 Hz in this oscillator) from beat 1 to beat 2
+    let mut smf = b"MThd\0\0\0\x06\0\x01\0\x02\0\x60".to_vec();
+    let tempo_track = [0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40, 0x00, 0xFF, 0x2F, 0x00];
+    let note_track = [0x60, 0x90, 57, 127, 0x60, 57, 0, 0x00, 0xFF, 0x2F, 0x00];
+    for track in [&tempo_track[..], &note_track[..]] {
+        smf.extend_from_slice(b"MTrk");
+        smf.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        smf.extend_from_slice(track);
+    }
+
+    let events = pure_audio_io::read_smf(Cursor::new(smf), 48000.0).unwrap();
+    let [[output]] = render(oscillator::process, 48000.0, 3 * 48000, &[], &[], &events);
+
+    assert!(output[..48000].iter().all(|&sample| sample == 0.0));
+    // one second of 440 Hz has 880 zero crossings, the quantized phase increment detunes slightly
+    let crossings = zero_crossings(&output[48000..96000]);
+    assert!((860..=900).contains(&crossings), "{crossings} zero crossings");
+    assert!(output[96000..].iter().all(|&sample| sample == 0.0));
+}
+
+#[test]
+fn pitch_bend_shifts_by_up_to_two_semitones() {
+    let events = [
+        TimedEvent::new(0, 0, EventKind::NoteOn { key: 57, velocity: 127 }),
+        TimedEvent::new(48000, 0, EventKind::PitchBend { value: 16383 }),
+        TimedEvent::new(96000, 0, EventKind::PitchBend { value: 0 }),
+        TimedEvent::new(144000, 0, EventKind::PitchBend { value: EventKind::PITCH_BEND_CENTER }),
+    ];
+    let [[output]] = render(oscillator::process, 48000.0, 4 * 48000, &[], &[], &events);
+
+    // seconds at full deflection up and down against the unbent pitch after them
+    let [up, down, center] = [48000, 96000, 144000].map(|start| zero_crossings(&output[start..start + 48000]) as f32);
+    let semitone = 2f32.powf(1.0 / 12.0);
+    assert!((up / center - semitone.powi(2)).abs() < 0.005, "{up} against {center} zero crossings");
+    assert!((down / center - semitone.powi(-2)).abs() < 0.005, "{down} against {center} zero crossings");
+}
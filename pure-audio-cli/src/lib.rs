@@ -0,0 +1,16 @@
+//! Runs pure-audio processors on audio files from the command line.
+//!
+//! A binary registers its processors and runs the command line with them:
+//!
+//! ```ignore
+//! use pure_audio_cli::RegisteredProcessor;
+//!
+//! fn main() -> std::process::ExitCode {
+//!     pure_audio_cli::run(&[RegisteredProcessor::new("Gain", gain::process)])
+//! }
+//! ```
+mod registry;
+mod runner;
+
+pub use registry::*;
+pub use runner::*;
@@ -0,0 +1,10 @@
+// runs the example processors
+use pure_audio_cli::RegisteredProcessor;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    pure_audio_cli::run(&[
+        RegisteredProcessor::new("Gain", gain::process),
+        RegisteredProcessor::new("Oscillator", oscillator::process),
+    ])
+}
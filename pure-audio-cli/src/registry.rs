@@ -0,0 +1,67 @@
+use pure_audio::{render, IntoProcessor, ParameterChange, ParameterDescriptor, TimedEvent};
+
+type Render = Box<dyn Fn(f32, usize, &[Vec<f32>], &[ParameterChange], &[TimedEvent]) -> Vec<Vec<f32>>>;
+
+/// A processor the command line can list and run, by its name regardless of case.
+pub struct RegisteredProcessor {
+    pub name: &'static str,
+    pub is_instrument: bool,
+    pub num_inputs: usize,
+    pub num_outputs: usize,
+    pub num_channels: usize,
+    pub descriptors: Vec<ParameterDescriptor>,
+    render: Render,
+}
+
+impl RegisteredProcessor {
+    pub fn new<
+        F,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const BLOCK_SIZE: usize,
+        const NUM_PARAMS: usize,
+        Params,
+        S,
+    >(
+        name: &'static str,
+        process: F,
+    ) -> Self
+    where
+        F: 'static + Copy + IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, Params, S>,
+    {
+        Self {
+            name,
+            is_instrument: IS_INSTRUMENT,
+            num_inputs: NUM_INPUTS,
+            num_outputs: NUM_OUTPUTS,
+            num_channels: NUM_CHANNELS,
+            descriptors: F::get_parameter_descriptors().to_vec(),
+            render: Box::new(move |sample_rate, num_frames, inputs, parameters, events| {
+                // inputs are flattened as input * NUM_CHANNELS + channel, missing channels are silent
+                let inputs = std::array::from_fn(|input| {
+                    std::array::from_fn(|channel| {
+                        inputs.get(input * NUM_CHANNELS + channel).map_or(&[][..], Vec::as_slice)
+                    })
+                });
+                render(process, sample_rate, num_frames, &inputs, parameters, events)
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }),
+        }
+    }
+
+    /// Renders with inputs and outputs flattened as `input * num_channels + channel`.
+    pub fn render(
+        &self,
+        sample_rate: f32,
+        num_frames: usize,
+        inputs: &[Vec<f32>],
+        parameters: &[ParameterChange],
+        events: &[TimedEvent],
+    ) -> Vec<Vec<f32>> {
+        (self.render)(sample_rate, num_frames, inputs, parameters, events)
+    }
+}
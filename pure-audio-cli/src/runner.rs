@@ -0,0 +1,216 @@
+use crate::RegisteredProcessor;
+use pure_audio::ParameterChange;
+use pure_audio_io::{open_smf, SampleFormat, Wav, WavSpec};
+use std::{error::Error, path::Path, process::ExitCode};
+
+fn usage(program: &str) -> String {
+    format!(
+        "\
+Usage:
+    {program} list
+    {program} run <processor> [options]
+
+Options:
+    -i, --input <file.wav>      input audio, required for effects
+    -o, --output <file.wav>     output audio
+    -m, --midi <file.mid>       Standard MIDI File driving an instrument
+    -p, --param <name=value>    set a parameter, clamped to its range (repeatable)
+    -r, --sample-rate <hz>      sample rate for instruments [default: 48000]
+    -t, --tail <seconds>        rendered time after the last MIDI event [default: 1]
+    -f, --format <format>       output format: pcm16, pcm24, f32, f64 [default: input format or f32]"
+    )
+}
+
+const DEFAULT_SAMPLE_RATE: u32 = 48000;
+const DEFAULT_TAIL: f32 = 1.0;
+
+#[derive(Default)]
+struct RunOptions {
+    processor: String,
+    input: Option<String>,
+    output: Option<String>,
+    midi: Option<String>,
+    parameters: Vec<(String, f32)>,
+    sample_rate: Option<u32>,
+    tail: Option<f32>,
+    format: Option<SampleFormat>,
+}
+
+/// Runs the command line of the process with `processors`, printing errors and the usage to stderr.
+pub fn run(processors: &[RegisteredProcessor]) -> ExitCode {
+    match run_with_args(processors, &std::env::args().collect::<Vec<_>>()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs `args`, starting with the program name, with `processors`: `list`, or `run <processor> [options]`.
+pub fn run_with_args(processors: &[RegisteredProcessor], args: &[String]) -> Result<(), Box<dyn Error>> {
+    // the usage names the binary the processors are registered in
+    let program = args
+        .first()
+        .and_then(|program| Path::new(program).file_stem()?.to_str())
+        .unwrap_or("pure-audio-cli");
+
+    match args.get(1).map(String::as_str) {
+        Some("list") => {
+            list(processors);
+            Ok(())
+        }
+        Some("run") => run_processor(program, processors, parse_run_options(program, &args[2..])?),
+        _ => Err(usage(program).into()),
+    }
+}
+
+fn list(processors: &[RegisteredProcessor]) {
+    for processor in processors {
+        let kind = if processor.is_instrument { "instrument" } else { "effect" };
+        println!(
+            "{} ({kind}, {} inputs, {} outputs, {} channels)",
+            processor.name, processor.num_inputs, processor.num_outputs, processor.num_channels
+        );
+        for descriptor in &processor.descriptors {
+            println!(
+                "    {}: default {}, range {}..={}, {}",
+                descriptor.name,
+                descriptor.default_value,
+                descriptor.min_value,
+                descriptor.max_value,
+                descriptor.automation_rate
+            );
+        }
+    }
+}
+
+fn parse_run_options(program: &str, args: &[String]) -> Result<RunOptions, Box<dyn Error>> {
+    let mut args = args.iter();
+    let mut options = RunOptions {
+        processor: args.next().ok_or_else(|| usage(program))?.clone(),
+        ..Default::default()
+    };
+
+    while let Some(option) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {option}"));
+        match option.as_str() {
+            "-i" | "--input" => options.input = Some(value()?.clone()),
+            "-o" | "--output" => options.output = Some(value()?.clone()),
+            "-m" | "--midi" => options.midi = Some(value()?.clone()),
+            "-p" | "--param" => {
+                let value = value()?;
+                let (name, value) = value.split_once('=').ok_or_else(|| format!("expected name=value, got {value}"))?;
+                // "nan" and "inf" parse, but no parameter range can hold them
+                let value: f32 = value.parse()?;
+                if !value.is_finite() {
+                    return Err(format!("{name} must be a finite number, got {value}").into());
+                }
+                options.parameters.push((name.to_string(), value));
+            }
+            "-r" | "--sample-rate" => options.sample_rate = Some(value()?.parse()?),
+            "-t" | "--tail" => options.tail = Some(value()?.parse()?),
+            "-f" | "--format" => options.format = Some(parse_format(value()?)?),
+            _ => return Err(format!("unknown option {option}\n\n{}", usage(program)).into()),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_format(format: &str) -> Result<SampleFormat, Box<dyn Error>> {
+    match format {
+        "pcm16" => Ok(SampleFormat::Pcm16),
+        "pcm24" => Ok(SampleFormat::Pcm24),
+        "f32" => Ok(SampleFormat::Float32),
+        "f64" => Ok(SampleFormat::Float64),
+        _ => Err(format!("unknown format {format}").into()),
+    }
+}
+
+fn run_processor(program: &str, processors: &[RegisteredProcessor], options: RunOptions) -> Result<(), Box<dyn Error>> {
+    let processor = processors
+        .iter()
+        .find(|processor| processor.name.eq_ignore_ascii_case(&options.processor))
+        .ok_or_else(|| format!("unknown processor {}, see `{program} list`", options.processor))?;
+
+    let parameters = parameter_changes(processor, &options.parameters)?;
+
+    let input = options.input.as_deref().map(Wav::open).transpose()?;
+    let sample_rate = match (&input, options.sample_rate) {
+        (Some(input), Some(sample_rate)) if input.spec.sample_rate != sample_rate => {
+            return Err(format!("input sample rate is {} Hz, resampling is not supported", input.spec.sample_rate).into());
+        }
+        (Some(input), _) => input.spec.sample_rate,
+        (None, sample_rate) => sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE),
+    };
+
+    let events = match &options.midi {
+        Some(midi) if processor.is_instrument => open_smf(midi, sample_rate as f32)?,
+        Some(_) => return Err(format!("{} is an effect and doesn't take MIDI input", processor.name).into()),
+        None => vec![],
+    };
+
+    let num_frames = match &input {
+        Some(input) => input.num_frames(),
+        None if processor.is_instrument => {
+            let last_frame = events.iter().map(|event| event.frame as usize).max().unwrap_or(0);
+            last_frame + (options.tail.unwrap_or(DEFAULT_TAIL) * sample_rate as f32) as usize
+        }
+        None => return Err(format!("{} is an effect and requires --input", processor.name).into()),
+    };
+
+    if let Some(input) = &input {
+        let expected = processor.num_inputs * processor.num_channels;
+        if input.channels.len() != expected {
+            eprintln!("warning: {} expects {expected} input channels, input has {}", processor.name, input.channels.len());
+        }
+    }
+
+    let inputs = input.as_ref().map_or(&[][..], |input| input.channels.as_slice());
+    let outputs = processor.render(sample_rate as f32, num_frames, inputs, &parameters, &events);
+
+    for (index, channel) in outputs.iter().enumerate() {
+        let peak = channel.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        let rms = (channel.iter().map(|sample| sample * sample).sum::<f32>() / channel.len().max(1) as f32).sqrt();
+        println!(
+            "output {} channel {}: peak {:.2} dBFS, rms {:.2} dBFS",
+            index / processor.num_channels,
+            index % processor.num_channels,
+            decibels(peak),
+            decibels(rms)
+        );
+    }
+
+    if let Some(output) = &options.output {
+        let format = options.format.or(input.map(|input| input.spec.format)).unwrap_or(SampleFormat::Float32);
+        let spec = WavSpec { sample_rate, num_channels: outputs.len() as u16, format };
+        Wav::new(spec, outputs)?.save(output)?;
+    }
+
+    Ok(())
+}
+
+// parameters are set from the first frame on
+fn parameter_changes(processor: &RegisteredProcessor, parameters: &[(String, f32)]) -> Result<Vec<ParameterChange>, Box<dyn Error>> {
+    parameters
+        .iter()
+        .map(|(name, value)| {
+            let (index, descriptor) = processor
+                .descriptors
+                .iter()
+                .enumerate()
+                .find(|(_, descriptor)| descriptor.name == name)
+                .ok_or_else(|| format!("{} has no parameter {name}", processor.name))?;
+            let clamped = value.clamp(descriptor.min_value, descriptor.max_value);
+            if clamped != *value {
+                eprintln!("warning: {name} clamped to {clamped}");
+            }
+            Ok(ParameterChange { frame: 0, index, value: clamped })
+        })
+        .collect()
+}
+
+fn decibels(amplitude: f32) -> f32 {
+    20.0 * amplitude.log10()
+}
@@ -0,0 +1,114 @@
+use pure_audio_io::{SampleFormat, Wav, WavSpec};
+use std::{path::PathBuf, process::Command};
+
+struct Output {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+fn cli(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_pure-audio-cli")).args(args).output().unwrap();
+    Output {
+        success: output.status.success(),
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+    }
+}
+
+fn path(name: &str) -> String {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name).to_str().unwrap().to_string()
+}
+
+// mono, 100 frames of 0.5
+fn input(name: &str) -> String {
+    let path = path(name);
+    let spec = WavSpec { sample_rate: 48000, num_channels: 1, format: SampleFormat::Float32 };
+    Wav::new(spec, vec![vec![0.5; 100]]).unwrap().save(&path).unwrap();
+    path
+}
+
+#[test]
+fn reports_peak_and_rms_and_writes_the_output() {
+    let input = input("report.wav");
+    let output = path("report_output.wav");
+    // processor names match regardless of case
+    let result = cli(&["run", "gain", "-i", &input, "-o", &output, "-p", "Volume=0.5", "-f", "pcm16"]);
+
+    assert!(result.success, "{}", result.stderr);
+    assert_eq!(result.stdout, "output 0 channel 0: peak -12.04 dBFS, rms -12.04 dBFS\n");
+    let output = Wav::open(&output).unwrap();
+    assert_eq!(output.spec.format, SampleFormat::Pcm16);
+    assert_eq!(output.channels, [vec![0.25; 100]]);
+}
+
+#[test]
+fn clamps_parameters_to_their_range() {
+    let input = input("clamp.wav");
+    let result = cli(&["run", "Gain", "-i", &input, "--param", "Volume=2"]);
+
+    assert!(result.success, "{}", result.stderr);
+    assert_eq!(result.stderr, "warning: Volume clamped to 1\n");
+    assert_eq!(result.stdout, "output 0 channel 0: peak -6.02 dBFS, rms -6.02 dBFS\n");
+}
+
+#[test]
+fn rejects_unknown_and_non_finite_parameters() {
+    let input = input("unknown.wav");
+    for (param, error) in [
+        // parameter names are exact
+        ("volume=1", "Gain has no parameter volume"),
+        ("Cutoff=1", "Gain has no parameter Cutoff"),
+        ("Volume=nan", "Volume must be a finite number, got NaN"),
+        ("Volume=-inf", "Volume must be a finite number, got -inf"),
+        ("Volume", "expected name=value, got Volume"),
+    ] {
+        let result = cli(&["run", "Gain", "-i", &input, "-p", param]);
+        assert!(!result.success);
+        assert_eq!(result.stderr.trim_end(), error);
+        assert_eq!(result.stdout, "");
+    }
+}
+
+#[test]
+fn rejects_invalid_options() {
+    let input = input("options.wav");
+    for (args, error) in [
+        (&["run", "Gain", "-i"][..], "missing value for -i"),
+        (&["run", "Gain", "-i", &input, "-f", "pcm8"], "unknown format pcm8"),
+        (&["run", "Gain", "-i", &input, "-r", "fast"], "invalid digit found in string"),
+        (&["run", "Reverb", "-i", &input], "unknown processor Reverb, see `pure-audio-cli list`"),
+        (&["run", "Gain"], "Gain is an effect and requires --input"),
+    ] {
+        let result = cli(args);
+        assert!(!result.success);
+        assert_eq!(result.stderr.trim_end(), error, "{args:?}");
+    }
+
+    let result = cli(&["run", "Gain", "--verbose"]);
+    assert!(result.stderr.starts_with("unknown option --verbose\n\nUsage:"));
+    assert!(cli(&["run"]).stderr.starts_with("Usage:"));
+}
+
+#[test]
+fn renders_an_instrument_from_a_midi_file() {
+    // format 0, 96 ticks per quarter at the default 120 bpm: a note from 0 to 0.5 s
+    let track = [0x00, 0x90, 60, 100, 0x60, 0x80, 60, 0, 0x00, 0xFF, 0x2F, 0x00];
+    let mut smf = b"MThd\0\0\0\x06\0\0\0\x01\0\x60MTrk\0\0\0".to_vec();
+    smf.push(track.len() as u8);
+    smf.extend_from_slice(&track);
+    let midi = path("notes.mid");
+    std::fs::write(&midi, smf).unwrap();
+    let output = path("notes.wav");
+
+    let result = cli(&["run", "Oscillator", "-m", &midi, "-r", "1000", "-t", "0.25", "-o", &output]);
+    assert!(result.success, "{}", result.stderr);
+    let output = Wav::open(&output).unwrap();
+    // the last event plus the tail
+    assert_eq!(output.num_frames(), 750);
+    assert_eq!(output.spec.sample_rate, 1000);
+    assert!(output.channels[0][..500].iter().any(|sample| *sample != 0.0));
+
+    let result = cli(&["run", "Gain", "-i", &input("midi.wav"), "-m", &midi]);
+    assert_eq!(result.stderr, "Gain is an effect and doesn't take MIDI input\n");
+}
@@ -0,0 +1,43 @@
+use pure_audio::{EffectAudioData, InputBuffer, OutputBuffer};
+use pure_audio_cli::{run_with_args, RegisteredProcessor};
+use pure_audio_io::{SampleFormat, Wav, WavSpec};
+use std::path::PathBuf;
+
+fn invert(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData<1, 1, 1>,
+) {
+    for (input_sample, output_sample) in input.iter().zip(output) {
+        *output_sample = -input_sample;
+    }
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn runs_registered_processors() {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let [input, output] = ["library.wav", "library_output.wav"].map(|name| directory.join(name).display().to_string());
+    let spec = WavSpec { sample_rate: 48000, num_channels: 1, format: SampleFormat::Float32 };
+    Wav::new(spec, vec![vec![0.5; 100]]).unwrap().save(&input).unwrap();
+
+    let processors = [RegisteredProcessor::new("Invert", invert)];
+    run_with_args(&processors, &args(&["my-tool", "run", "invert", "-i", &input, "-o", &output])).unwrap();
+
+    assert_eq!(Wav::open(&output).unwrap().channels, [vec![-0.5; 100]]);
+}
+
+#[test]
+fn names_the_program_in_errors() {
+    let processors = [RegisteredProcessor::new("Invert", invert)];
+
+    let error = run_with_args(&processors, &args(&["/usr/bin/my-tool"])).unwrap_err().to_string();
+    assert!(error.contains("    my-tool run <processor> [options]"), "{error}");
+    let error = run_with_args(&processors, &args(&["my-tool", "run", "gain"])).unwrap_err().to_string();
+    assert_eq!(error, "unknown processor gain, see `my-tool list`");
+}
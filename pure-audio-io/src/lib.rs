@@ -0,0 +1,6 @@
+mod smf;
+mod wav;
+
+// re-export
+pub use smf::*;
+pub use wav::*;
@@ -0,0 +1,224 @@
+use pure_audio::{midi::MidiParser, Event, TimedEvent};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+// microseconds per quarter note when no tempo is set (120 bpm)
+const DEFAULT_TEMPO: u32 = 500_000;
+
+const META_END_OF_TRACK: u8 = 0x2F;
+const META_SET_TEMPO: u8 = 0x51;
+
+#[derive(Debug)]
+pub enum SmfError {
+    Io(std::io::Error),
+    InvalidHeader(&'static str),
+    InvalidTrack(&'static str),
+    Unsupported(&'static str),
+}
+
+impl Display for SmfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SmfError::Io(error) => write!(f, "I/O error: {error}"),
+            SmfError::InvalidHeader(reason) => write!(f, "invalid MIDI file header: {reason}"),
+            SmfError::InvalidTrack(reason) => write!(f, "invalid MIDI track: {reason}"),
+            SmfError::Unsupported(reason) => write!(f, "unsupported MIDI file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SmfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SmfError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SmfError {
+    fn from(error: std::io::Error) -> Self {
+        SmfError::Io(error)
+    }
+}
+
+/// Reads a Standard MIDI File into events at absolute frames at `sample_rate`.
+///
+/// Supports format 0 and 1 files with either a ticks per quarter note (tempo mapped) or an SMPTE division. The tracks
+/// of a format 1 file are merged, events on the same tick keep their track order.
+pub fn read_smf(mut reader: impl Read, sample_rate: f32) -> Result<Vec<TimedEvent>, SmfError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.len() < 14 || &bytes[0..4] != b"MThd" {
+        return Err(SmfError::InvalidHeader("not a Standard MIDI File"));
+    }
+    let header_size = u32::from_be_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    if header_size < 6 {
+        return Err(SmfError::InvalidHeader("header chunk too short"));
+    }
+    let format = u16::from_be_bytes([bytes[8], bytes[9]]);
+    let division = Division::new(u16::from_be_bytes([bytes[12], bytes[13]]))?;
+    if format > 1 {
+        return Err(SmfError::Unsupported("format 2 (independent sequences)"));
+    }
+
+    let mut track_events = vec![];
+    let mut chunks = bytes.get(8 + header_size..).unwrap_or_default();
+    while chunks.len() >= 8 {
+        let size = u32::from_be_bytes(chunks[4..8].try_into().unwrap()) as usize;
+        let body = chunks.get(8..8 + size).ok_or(SmfError::InvalidTrack("chunk exceeds file size"))?;
+        // unknown chunks must be skipped
+        if &chunks[0..4] == b"MTrk" {
+            read_track(body, &mut track_events)?;
+        }
+        chunks = &chunks[8 + size..];
+    }
+
+    // stable sort keeps the track order, and within a track the file order, of events on the same tick
+    track_events.sort_by_key(|(tick, _)| *tick);
+
+    let mut events = vec![];
+    let mut tempo = DEFAULT_TEMPO;
+    let mut tick = 0;
+    let mut seconds = 0.0;
+    for (event_tick, event) in track_events {
+        seconds += division.seconds(event_tick - tick, tempo);
+        tick = event_tick;
+        match event {
+            TrackEvent::Tempo(new_tempo) => tempo = new_tempo,
+            TrackEvent::Channel(event) => {
+                events.push(TimedEvent::new((seconds * sample_rate as f64).round() as u64, event.channel, event.kind))
+            }
+        }
+    }
+    Ok(events)
+}
+
+pub fn open_smf(path: impl AsRef<Path>, sample_rate: f32) -> Result<Vec<TimedEvent>, SmfError> {
+    read_smf(BufReader::new(File::open(path)?), sample_rate)
+}
+
+#[derive(Copy, Clone)]
+enum Division {
+    TicksPerQuarter(u16),
+    Smpte { ticks_per_second: f64 },
+}
+
+impl Division {
+    fn new(division: u16) -> Result<Self, SmfError> {
+        if division & 0x8000 == 0 {
+            return match division {
+                0 => Err(SmfError::InvalidHeader("division is 0")),
+                ticks => Ok(Division::TicksPerQuarter(ticks)),
+            };
+        }
+
+        // negative SMPTE format in the upper byte, ticks per frame in the lower byte
+        let frames_per_second = match -((division >> 8) as u8 as i8) {
+            24 => 24.0,
+            25 => 25.0,
+            29 => 30000.0 / 1001.0,
+            30 => 30.0,
+            _ => return Err(SmfError::InvalidHeader("unknown SMPTE format")),
+        };
+        match division & 0xFF {
+            0 => Err(SmfError::InvalidHeader("division is 0")),
+            ticks_per_frame => Ok(Division::Smpte { ticks_per_second: frames_per_second * ticks_per_frame as f64 }),
+        }
+    }
+
+    fn seconds(&self, ticks: u64, tempo: u32) -> f64 {
+        match self {
+            Division::TicksPerQuarter(ticks_per_quarter) => {
+                ticks as f64 * tempo as f64 / 1_000_000.0 / *ticks_per_quarter as f64
+            }
+            // SMPTE time is absolute, tempo doesn't apply
+            Division::Smpte { ticks_per_second } => ticks as f64 / ticks_per_second,
+        }
+    }
+}
+
+enum TrackEvent {
+    Tempo(u32),
+    Channel(Event),
+}
+
+// appends the tempo and channel events of a track with their absolute tick
+fn read_track(mut track: &[u8], events: &mut Vec<(u64, TrackEvent)>) -> Result<(), SmfError> {
+    let mut parser = MidiParser::new();
+    let mut running_status = None;
+    let mut tick = 0u64;
+
+    while !track.is_empty() {
+        tick += read_variable_length(&mut track)? as u64;
+
+        let status = match track.first() {
+            Some(&byte) if byte & 0x80 != 0 => {
+                track = &track[1..];
+                byte
+            }
+            Some(_) => running_status.ok_or(SmfError::InvalidTrack("data byte without status"))?,
+            None => return Err(SmfError::InvalidTrack("missing event")),
+        };
+
+        match status {
+            0xFF => {
+                let kind = *track.first().ok_or(SmfError::InvalidTrack("missing meta event type"))?;
+                track = &track[1..];
+                let data = read_data(&mut track)?;
+                match kind {
+                    META_SET_TEMPO if data.len() == 3 => {
+                        events.push((tick, TrackEvent::Tempo(u32::from_be_bytes([0, data[0], data[1], data[2]]))));
+                    }
+                    META_END_OF_TRACK => break,
+                    _ => {}
+                }
+                running_status = None;
+            }
+            0xF0 | 0xF7 => {
+                read_data(&mut track)?;
+                running_status = None;
+            }
+            0xF1..=0xFE => return Err(SmfError::InvalidTrack("unexpected system message")),
+            _ => {
+                let length = match status & 0xF0 {
+                    0xC0 | 0xD0 => 1,
+                    _ => 2,
+                };
+                let data = track.get(..length).ok_or(SmfError::InvalidTrack("truncated channel message"))?;
+                track = &track[length..];
+                parser.push(status, 0);
+                events.extend(parser.parse(data, 0).map(|event| (tick, TrackEvent::Channel(event))));
+                running_status = Some(status);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_variable_length(track: &mut &[u8]) -> Result<u32, SmfError> {
+    let mut value = 0u32;
+    // at most 4 bytes
+    for _ in 0..4 {
+        let (&byte, rest) = track.split_first().ok_or(SmfError::InvalidTrack("truncated variable length quantity"))?;
+        *track = rest;
+        value = (value << 7) | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(SmfError::InvalidTrack("variable length quantity too long"))
+}
+
+fn read_data<'a>(track: &mut &'a [u8]) -> Result<&'a [u8], SmfError> {
+    let length = read_variable_length(track)? as usize;
+    let data = track.get(..length).ok_or(SmfError::InvalidTrack("truncated event data"))?;
+    *track = &track[length..];
+    Ok(data)
+}
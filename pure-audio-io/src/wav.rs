@@ -0,0 +1,339 @@
+use pure_audio::Buffer;
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+// KSDATAFORMAT_SUBTYPE_* without the format code in the first two bytes
+const SUBFORMAT_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SampleFormat {
+    Pcm16,
+    Pcm24,
+    Float32,
+    Float64,
+}
+
+impl SampleFormat {
+    pub fn bits_per_sample(&self) -> u16 {
+        match self {
+            SampleFormat::Pcm16 => 16,
+            SampleFormat::Pcm24 => 24,
+            SampleFormat::Float32 => 32,
+            SampleFormat::Float64 => 64,
+        }
+    }
+
+    fn format_code(&self) -> u16 {
+        match self {
+            SampleFormat::Pcm16 | SampleFormat::Pcm24 => WAVE_FORMAT_PCM,
+            SampleFormat::Float32 | SampleFormat::Float64 => WAVE_FORMAT_IEEE_FLOAT,
+        }
+    }
+
+    fn from_format(format_code: u16, bits_per_sample: u16) -> Result<Self, WavError> {
+        match (format_code, bits_per_sample) {
+            (WAVE_FORMAT_PCM, 16) => Ok(SampleFormat::Pcm16),
+            (WAVE_FORMAT_PCM, 24) => Ok(SampleFormat::Pcm24),
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => Ok(SampleFormat::Float32),
+            (WAVE_FORMAT_IEEE_FLOAT, 64) => Ok(SampleFormat::Float64),
+            _ => Err(WavError::Unsupported { format_code, bits_per_sample }),
+        }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> f32 {
+        match self {
+            SampleFormat::Pcm16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            // sign-extend by placing the 24 bits in the upper bytes of an i32
+            SampleFormat::Pcm24 => (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8388608.0,
+            SampleFormat::Float32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            SampleFormat::Float64 => f64::from_le_bytes(bytes[..8].try_into().unwrap()) as f32,
+        }
+    }
+
+    fn encode(&self, sample: f32, bytes: &mut Vec<u8>) {
+        match self {
+            SampleFormat::Pcm16 => {
+                let value = (sample * 32768.0).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            SampleFormat::Pcm24 => {
+                let value = (sample * 8388608.0).round().clamp(-8388608.0, 8388607.0) as i32;
+                bytes.extend_from_slice(&value.to_le_bytes()[..3]);
+            }
+            SampleFormat::Float32 => bytes.extend_from_slice(&sample.to_le_bytes()),
+            SampleFormat::Float64 => bytes.extend_from_slice(&(sample as f64).to_le_bytes()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WavSpec {
+    pub sample_rate: u32,
+    pub num_channels: u16,
+    pub format: SampleFormat,
+}
+
+#[derive(Debug)]
+pub enum WavError {
+    Io(std::io::Error),
+    InvalidHeader(&'static str),
+    Unsupported { format_code: u16, bits_per_sample: u16 },
+    ChannelMismatch { expected: usize, found: usize },
+    UnequalChannelLengths,
+    /// The audio doesn't fit the 16 and 32 bit sizes of the header.
+    TooLarge(&'static str),
+}
+
+impl Display for WavError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WavError::Io(error) => write!(f, "I/O error: {error}"),
+            WavError::InvalidHeader(reason) => write!(f, "invalid WAVE header: {reason}"),
+            WavError::Unsupported { format_code, bits_per_sample } => {
+                write!(f, "unsupported sample format {format_code:#06x} with {bits_per_sample} bits per sample")
+            }
+            WavError::ChannelMismatch { expected, found } => {
+                write!(f, "expected {expected} channels, found {found}")
+            }
+            WavError::UnequalChannelLengths => write!(f, "channels have different lengths"),
+            WavError::TooLarge(reason) => write!(f, "too large for a WAVE file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for WavError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WavError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for WavError {
+    fn from(error: std::io::Error) -> Self {
+        WavError::Io(error)
+    }
+}
+
+/// Audio with one `Vec` of samples per channel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wav {
+    pub spec: WavSpec,
+    pub channels: Vec<Vec<f32>>,
+}
+
+impl Wav {
+    pub fn new(spec: WavSpec, channels: Vec<Vec<f32>>) -> Result<Self, WavError> {
+        if channels.len() != spec.num_channels as usize {
+            return Err(WavError::ChannelMismatch { expected: spec.num_channels as usize, found: channels.len() });
+        }
+        if channels.iter().any(|channel| channel.len() != channels[0].len()) {
+            return Err(WavError::UnequalChannelLengths);
+        }
+        Ok(Self { spec, channels })
+    }
+
+    pub fn num_frames(&self) -> usize {
+        self.channels.first().map_or(0, Vec::len)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, WavError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), WavError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn read(mut reader: impl Read) -> Result<Self, WavError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err(WavError::InvalidHeader("not a RIFF/WAVE file"));
+        }
+
+        let mut spec = None;
+        let mut data = None;
+        let mut chunks = &bytes[12..];
+        while chunks.len() >= 8 {
+            let id = &chunks[0..4];
+            let size = u32::from_le_bytes(chunks[4..8].try_into().unwrap()) as usize;
+            let body = 8usize
+                .checked_add(size)
+                .and_then(|end| chunks.get(8..end))
+                .ok_or(WavError::InvalidHeader("chunk exceeds file size"))?;
+            match id {
+                b"fmt " => spec = Some(read_format(body)?),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+            // chunks are padded to an even size, 8 + size is within the file and can't overflow
+            chunks = chunks.get(8 + size + size % 2..).unwrap_or_default();
+        }
+
+        let spec = spec.ok_or(WavError::InvalidHeader("missing fmt chunk"))?;
+        let data = data.ok_or(WavError::InvalidHeader("missing data chunk"))?;
+
+        let sample_size = spec.format.bits_per_sample() as usize / 8;
+        let frame_size = sample_size * spec.num_channels as usize;
+        let mut channels = vec![Vec::with_capacity(data.len() / frame_size); spec.num_channels as usize];
+        // a trailing partial frame is ignored
+        for frame in data.chunks_exact(frame_size) {
+            for (channel, sample) in channels.iter_mut().zip(frame.chunks_exact(sample_size)) {
+                channel.push(spec.format.decode(sample));
+            }
+        }
+
+        Ok(Self { spec, channels })
+    }
+
+    /// Writes WAVE_FORMAT_EXTENSIBLE when required: more than 2 channels or more than 16 bits per sample.
+    ///
+    /// Fails with [`WavError::TooLarge`] when a frame exceeds 65535 bytes, a second of audio or the file 4 GiB.
+    pub fn write(&self, mut writer: impl Write) -> Result<(), WavError> {
+        let WavSpec { sample_rate, num_channels, format } = self.spec;
+        let bits_per_sample = format.bits_per_sample();
+        let block_align = u16::try_from(u32::from(num_channels) * u32::from(bits_per_sample) / 8)
+            .map_err(|_| WavError::TooLarge("frame exceeds 65535 bytes"))?;
+        let byte_rate = sample_rate
+            .checked_mul(u32::from(block_align))
+            .ok_or(WavError::TooLarge("second of audio exceeds 4 GiB"))?;
+        let extensible = num_channels > 2 || bits_per_sample > 16;
+
+        let mut fmt = vec![];
+        fmt.extend_from_slice(&(if extensible { WAVE_FORMAT_EXTENSIBLE } else { format.format_code() }).to_le_bytes());
+        fmt.extend_from_slice(&num_channels.to_le_bytes());
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&byte_rate.to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits_per_sample.to_le_bytes());
+        if extensible {
+            fmt.extend_from_slice(&22u16.to_le_bytes());
+            fmt.extend_from_slice(&bits_per_sample.to_le_bytes());
+            fmt.extend_from_slice(&channel_mask(num_channels).to_le_bytes());
+            fmt.extend_from_slice(&format.format_code().to_le_bytes());
+            fmt.extend_from_slice(&SUBFORMAT_GUID_TAIL);
+        }
+
+        // checked before encoding, which would need as much memory. The RIFF size counts everything after it
+        let data_size = self.num_frames() as u64 * u64::from(block_align);
+        let riff_size = u32::try_from(4 + (8 + fmt.len() as u64) + (8 + data_size + data_size % 2))
+            .map_err(|_| WavError::TooLarge("file exceeds 4 GiB"))?;
+
+        let mut data = Vec::with_capacity(data_size as usize);
+        for frame in 0..self.num_frames() {
+            for channel in &self.channels {
+                format.encode(channel[frame], &mut data);
+            }
+        }
+        let padding = data.len() % 2;
+
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&riff_size.to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&(fmt.len() as u32).to_le_bytes())?;
+        writer.write_all(&fmt)?;
+        writer.write_all(b"data")?;
+        writer.write_all(&(data_size as u32).to_le_bytes())?;
+        writer.write_all(&data)?;
+        writer.write_all(&[0; 1][..padding])?;
+        Ok(())
+    }
+
+    /// Channels as processor input, e.g. for [`pure_audio::render`].
+    pub fn input<const NUM_CHANNELS: usize>(&self) -> Result<[&[f32]; NUM_CHANNELS], WavError> {
+        if self.channels.len() != NUM_CHANNELS {
+            return Err(WavError::ChannelMismatch { expected: NUM_CHANNELS, found: self.channels.len() });
+        }
+        Ok(std::array::from_fn(|i| self.channels[i].as_slice()))
+    }
+
+    /// Splits the audio into blocks of a single input/output, the last block is padded with silence.
+    pub fn to_blocks<const NUM_CHANNELS: usize, const BLOCK_SIZE: usize>(
+        &self,
+    ) -> Result<Vec<Buffer<1, NUM_CHANNELS, BLOCK_SIZE>>, WavError> {
+        let channels = self.input::<NUM_CHANNELS>()?;
+        Ok((0..self.num_frames())
+            .step_by(BLOCK_SIZE)
+            .map(|start| {
+                [channels.map(|channel| {
+                    let mut block = [0.0; BLOCK_SIZE];
+                    let samples = &channel[start..channel.len().min(start + BLOCK_SIZE)];
+                    block[..samples.len()].copy_from_slice(samples);
+                    block
+                })]
+            })
+            .collect())
+    }
+
+    pub fn from_blocks<const NUM_CHANNELS: usize, const BLOCK_SIZE: usize>(
+        spec: WavSpec,
+        blocks: &[Buffer<1, NUM_CHANNELS, BLOCK_SIZE>],
+    ) -> Result<Self, WavError> {
+        let channels = (0..NUM_CHANNELS)
+            .map(|channel| blocks.iter().flat_map(|[block]| block[channel]).collect())
+            .collect();
+        Self::new(spec, channels)
+    }
+}
+
+fn read_format(body: &[u8]) -> Result<WavSpec, WavError> {
+    if body.len() < 16 {
+        return Err(WavError::InvalidHeader("fmt chunk too short"));
+    }
+    let u16_at = |offset: usize| u16::from_le_bytes([body[offset], body[offset + 1]]);
+
+    let mut format_code = u16_at(0);
+    let num_channels = u16_at(2);
+    let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+    let block_align = u16_at(12);
+    let bits_per_sample = u16_at(14);
+
+    if format_code == WAVE_FORMAT_EXTENSIBLE {
+        if body.len() < 40 {
+            return Err(WavError::InvalidHeader("fmt chunk too short for WAVE_FORMAT_EXTENSIBLE"));
+        }
+        if body[26..40] != SUBFORMAT_GUID_TAIL {
+            return Err(WavError::InvalidHeader("unknown WAVE_FORMAT_EXTENSIBLE sub format"));
+        }
+        format_code = u16_at(24);
+    }
+
+    if num_channels == 0 {
+        return Err(WavError::InvalidHeader("no channels"));
+    }
+    if sample_rate == 0 {
+        return Err(WavError::InvalidHeader("sample rate is 0"));
+    }
+    let format = SampleFormat::from_format(format_code, bits_per_sample)?;
+    if block_align as usize != num_channels as usize * bits_per_sample as usize / 8 {
+        return Err(WavError::InvalidHeader("block align doesn't match channels and sample size"));
+    }
+
+    Ok(WavSpec { sample_rate, num_channels, format })
+}
+
+// default speaker positions: front left, front right, front center, low frequency, ...
+fn channel_mask(num_channels: u16) -> u32 {
+    match num_channels {
+        1 => 0x4,
+        n if n < 32 => (1 << n) - 1,
+        _ => 0,
+    }
+}
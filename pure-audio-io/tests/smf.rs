@@ -0,0 +1,94 @@
+use pure_audio::{EventKind, TimedEvent};
+use pure_audio_io::{open_smf, read_smf, SmfError};
+use std::io::{Cursor, ErrorKind};
+
+fn smf(format: u16, division: u16, tracks: &[&[u8]]) -> Vec<u8> {
+    let mut bytes = b"MThd".to_vec();
+    bytes.extend_from_slice(&6u32.to_be_bytes());
+    bytes.extend_from_slice(&format.to_be_bytes());
+    bytes.extend_from_slice(&(tracks.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(&division.to_be_bytes());
+    for track in tracks {
+        bytes.extend_from_slice(b"MTrk");
+        bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(track);
+    }
+    bytes
+}
+
+fn note_on(frame: u64, key: u8) -> TimedEvent {
+    TimedEvent::new(frame, 0, EventKind::NoteOn { key, velocity: 100 })
+}
+
+#[test]
+fn applies_tempo_changes() {
+    // 480 ticks per quarter: a quarter at 120 bpm, then 60 bpm after a tempo change, with running status
+    let track = [
+        0x00, 0x90, 60, 100,
+        0x83, 0x60, 62, 100,
+        0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40,
+        0x83, 0x60, 0x90, 64, 100,
+        0x00, 0xFF, 0x2F, 0x00,
+    ];
+    let events = read_smf(Cursor::new(smf(0, 480, &[&track])), 1000.0).unwrap();
+    assert_eq!(events, [note_on(0, 60), note_on(500, 62), note_on(1500, 64)]);
+}
+
+#[test]
+fn skips_sysex_and_meta_events() {
+    // a SysEx message and a text event before a note a quarter later at the default 120 bpm
+    let track = [
+        0x00, 0xF0, 0x03, 0x7E, 0x7F, 0xF7,
+        0x00, 0xFF, 0x01, 0x02, b'h', b'i',
+        0x60, 0x90, 60, 100,
+    ];
+    let events = read_smf(Cursor::new(smf(0, 96, &[&track])), 1000.0).unwrap();
+    assert_eq!(events, [note_on(500, 60)]);
+
+    // running status doesn't survive a SysEx message
+    let track = [0x00, 0x90, 60, 100, 0x00, 0xF0, 0x01, 0xF7, 0x00, 62, 100];
+    let result = read_smf(Cursor::new(smf(0, 96, &[&track])), 1000.0);
+    assert!(matches!(result, Err(SmfError::InvalidTrack("data byte without status"))));
+}
+
+#[test]
+fn opens_files() {
+    let path = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("note.mid");
+    std::fs::write(&path, smf(0, 96, &[&[0x00, 0x90, 60, 100]])).unwrap();
+    assert_eq!(open_smf(&path, 1000.0).unwrap(), [note_on(0, 60)]);
+
+    let result = open_smf(path.with_file_name("missing.mid"), 1000.0);
+    assert!(matches!(result, Err(SmfError::Io(error)) if error.kind() == ErrorKind::NotFound));
+}
+
+#[test]
+fn merges_tracks_with_tempo_map() {
+    // tempo track at 60 bpm, notes in the second track
+    let tempo_track = [0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40, 0x00, 0xFF, 0x2F, 0x00];
+    let note_track = [0x00, 0x90, 60, 100, 0x60, 0x91, 62, 0, 0x00, 0xFF, 0x2F, 0x00];
+    let events = read_smf(Cursor::new(smf(1, 96, &[&tempo_track, &note_track])), 1000.0).unwrap();
+    assert_eq!(events, [
+        note_on(0, 60),
+        TimedEvent::new(1000, 1, EventKind::NoteOff { key: 62, velocity: 64 }),
+    ]);
+}
+
+#[test]
+fn uses_smpte_division() {
+    // 25 frames per second, 40 ticks per frame: 1000 ticks per second, tempo is ignored
+    let track = [0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40, 0x87, 0x68, 0x90, 60, 100];
+    let events = read_smf(Cursor::new(smf(0, 0xE728, &[&track])), 48000.0).unwrap();
+    assert_eq!(events, [note_on(48000, 60)]);
+}
+
+#[test]
+fn rejects_invalid_files() {
+    let read = |bytes: Vec<u8>| read_smf(Cursor::new(bytes), 48000.0);
+
+    assert!(matches!(read(b"RIFF".to_vec()), Err(SmfError::InvalidHeader(_))));
+    assert!(matches!(read(smf(2, 96, &[])), Err(SmfError::Unsupported(_))));
+    assert!(matches!(read(smf(0, 0, &[])), Err(SmfError::InvalidHeader("division is 0"))));
+    assert!(matches!(read(smf(0, 96, &[&[0x00, 60, 100]])), Err(SmfError::InvalidTrack("data byte without status"))));
+    assert!(matches!(read(smf(0, 96, &[&[0x00, 0x90, 60]])), Err(SmfError::InvalidTrack("truncated channel message"))));
+    assert!(matches!(read(smf(0, 96, &[&[0xFF, 0xFF, 0xFF, 0xFF, 0x00]])), Err(SmfError::InvalidTrack(_))));
+}
@@ -0,0 +1,102 @@
+use pure_audio_io::{SampleFormat, Wav, WavError, WavSpec};
+use std::io::Cursor;
+
+fn round_trip(format: SampleFormat, num_channels: u16) -> (Wav, Wav) {
+    let channels = (0..num_channels)
+        .map(|channel| (0..100).map(|frame| ((frame * 7 + channel as i32 * 13) % 64 - 32) as f32 / 32.0).collect())
+        .collect();
+    let wav = Wav::new(WavSpec { sample_rate: 44100, num_channels, format }, channels).unwrap();
+    let mut bytes = vec![];
+    wav.write(&mut bytes).unwrap();
+    (wav, Wav::read(Cursor::new(bytes)).unwrap())
+}
+
+fn header(format_code: u16, num_channels: u16, bits_per_sample: u16) -> Vec<u8> {
+    let block_align = num_channels * bits_per_sample / 8;
+    let mut bytes = vec![];
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&36u32.to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&format_code.to_le_bytes());
+    bytes.extend_from_slice(&num_channels.to_le_bytes());
+    bytes.extend_from_slice(&48000u32.to_le_bytes());
+    bytes.extend_from_slice(&(48000 * block_align as u32).to_le_bytes());
+    bytes.extend_from_slice(&block_align.to_le_bytes());
+    bytes.extend_from_slice(&bits_per_sample.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn round_trips_all_formats() {
+    for format in [SampleFormat::Pcm16, SampleFormat::Pcm24, SampleFormat::Float32, SampleFormat::Float64] {
+        for num_channels in [1, 2, 6] {
+            let (written, read) = round_trip(format, num_channels);
+            assert_eq!(written, read, "{format:?} with {num_channels} channels");
+        }
+    }
+}
+
+#[test]
+fn writes_extensible_format_when_required() {
+    let wav = Wav::new(WavSpec { sample_rate: 48000, num_channels: 6, format: SampleFormat::Pcm16 }, vec![vec![0.0]; 6]).unwrap();
+    let mut bytes = vec![];
+    wav.write(&mut bytes).unwrap();
+    assert_eq!(u16::from_le_bytes([bytes[20], bytes[21]]), 0xFFFE);
+}
+
+#[test]
+fn maps_onto_blocks() {
+    let (wav, _) = round_trip(SampleFormat::Float32, 2);
+    let blocks = wav.to_blocks::<2, 64>().unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[1][0][1][35], wav.channels[1][99]);
+    assert_eq!(blocks[1][0][1][36], 0.0);
+
+    let restored = Wav::from_blocks(wav.spec, &blocks).unwrap();
+    assert_eq!(restored.channels[0][..100], wav.channels[0][..]);
+    assert!(matches!(wav.to_blocks::<1, 64>(), Err(WavError::ChannelMismatch { expected: 1, found: 2 })));
+}
+
+#[test]
+fn rejects_malformed_headers() {
+    let read = |bytes: Vec<u8>| Wav::read(Cursor::new(bytes));
+
+    assert!(matches!(read(b"RIFX\0\0\0\0WAVE".to_vec()), Err(WavError::InvalidHeader(_))));
+    assert!(matches!(read(b"RIFF\0\0\0\0WAVE".to_vec()), Err(WavError::InvalidHeader("missing fmt chunk"))));
+    assert!(matches!(read(header(1, 1, 16)), Err(WavError::InvalidHeader("missing data chunk"))));
+    assert!(matches!(read(header(1, 1, 12)), Err(WavError::Unsupported { format_code: 1, bits_per_sample: 12 })));
+    assert!(matches!(read(header(1, 0, 16)), Err(WavError::InvalidHeader("no channels"))));
+
+    let mut truncated = header(3, 2, 32);
+    truncated.extend_from_slice(b"data");
+    truncated.extend_from_slice(&100u32.to_le_bytes());
+    truncated.extend_from_slice(&[0; 10]);
+    assert!(matches!(read(truncated), Err(WavError::InvalidHeader("chunk exceeds file size"))));
+
+    let mut misaligned = header(1, 2, 16);
+    misaligned[32] = 3;
+    assert!(matches!(read(misaligned), Err(WavError::InvalidHeader(_))));
+}
+
+#[test]
+fn rejects_oversized_chunks() {
+    let mut oversized = header(1, 1, 16);
+    oversized.extend_from_slice(b"data");
+    oversized.extend_from_slice(&u32::MAX.to_le_bytes());
+    oversized.extend_from_slice(&[0; 4]);
+    assert!(matches!(Wav::read(Cursor::new(oversized)), Err(WavError::InvalidHeader("chunk exceeds file size"))));
+}
+
+#[test]
+fn rejects_audio_the_header_cant_describe() {
+    let write = |sample_rate, num_channels| {
+        let spec = WavSpec { sample_rate, num_channels, format: SampleFormat::Float64 };
+        Wav::new(spec, vec![vec![0.0]; num_channels as usize]).unwrap().write(&mut vec![])
+    };
+
+    // frames of 8191 channels of 8 bytes fit in 65535 bytes, those of 8192 channels do not
+    assert!(matches!(write(48000, 8191), Ok(())));
+    assert!(matches!(write(48000, 8192), Err(WavError::TooLarge("frame exceeds 65535 bytes"))));
+    assert!(matches!(write(u32::MAX, 1), Err(WavError::TooLarge("second of audio exceeds 4 GiB"))));
+}
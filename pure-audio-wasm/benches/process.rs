@@ -0,0 +1,132 @@
+//! Per-block cost of `WasmProcessor::process`, the call the worklet makes every 128 frames.
+//!
+//! Run with `cargo bench -p pure-audio-wasm`.
+
+use pure_audio::{
+    AudioRate, EffectAudioData, EventKind, InputBuffer, InstrumentAudioData, OutputBuffer, ParameterAutomationRate,
+    ParameterDescriptor, ProcessorParameter,
+};
+use pure_audio_wasm::{create_wasm_processor, OverflowPolicy, WasmProcessor};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+const BLOCKS: u32 = 100_000;
+const BLOCK_LENGTH: f64 = 128.0;
+
+// counts allocations, which must stay at zero once the processor runs
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Copy, Clone)]
+struct Volume(f32);
+
+impl ProcessorParameter for Volume {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::A,
+        default_value: 1.0,
+        max_value: 1.0,
+        min_value: 0.0,
+        name: "Volume",
+    };
+
+    fn from_parameter(value: f32) -> Self {
+        Volume(value)
+    }
+}
+
+fn gain(
+    EffectAudioData {
+        inputs: InputBuffer([[left, right]]),
+        outputs: OutputBuffer([[left_output, right_output]]),
+        ..
+    }: EffectAudioData<1, 1, 2>,
+    volume: AudioRate<Volume>,
+) {
+    for (frame, Volume(volume)) in volume.iter().enumerate() {
+        left_output[frame] = left[frame] * volume;
+        right_output[frame] = right[frame] * volume;
+    }
+}
+
+// a square wave while a note is held
+fn square(
+    InstrumentAudioData {
+        events,
+        outputs: OutputBuffer([[output]]),
+        state: (held, phase),
+        ..
+    }: InstrumentAudioData<1, 1, 128, (bool, u32)>,
+) {
+    for event in events {
+        match event.kind {
+            EventKind::NoteOn { .. } => *held = true,
+            EventKind::NoteOff { .. } => *held = false,
+            _ => {}
+        }
+    }
+    for sample in output.iter_mut() {
+        *phase = phase.wrapping_add(1);
+        *sample = if *held && *phase % 100 < 50 { 0.5 } else { -0.5 };
+    }
+}
+
+fn bench(name: &str, mut processor: WasmProcessor, mut block: impl FnMut(&mut WasmProcessor, f64)) {
+    // warm up, lets the event buffers reach their steady state capacity
+    for i in 0..1000 {
+        block(&mut processor, i as f64 * BLOCK_LENGTH);
+    }
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for i in 0..BLOCKS {
+        block(&mut processor, black_box(f64::from(i) * BLOCK_LENGTH));
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+
+    println!(
+        "{name:<24} {:>8.1} ns/block {:>8.3} allocations/block",
+        elapsed.as_nanos() as f64 / f64::from(BLOCKS),
+        allocations as f64 / f64::from(BLOCKS)
+    );
+}
+
+fn main() {
+    bench("gain", create_wasm_processor(gain, 48000.0), |processor, frame| processor.process(frame));
+
+    bench("instrument", create_wasm_processor(square, 48000.0), |processor, frame| processor.process(frame));
+
+    bench("instrument with events", create_wasm_processor(square, 48000.0), |processor, frame| {
+        processor.note_on(0, 60, 100, frame + 16.0);
+        processor.note_off(0, 60, 0, frame + 80.0);
+        processor.process(frame);
+    });
+
+    // more events than the queue holds, e.g. a MIDI file dump
+    let processor = create_wasm_processor(square, 48000.0).with_event_queue(256, OverflowPolicy::MergeRedundant);
+    bench("instrument with bursts", processor, |processor, frame| {
+        for value in 0..1000 {
+            processor.control_change(0, (value % 8) as u8, (value % 128) as u8, frame);
+        }
+        processor.process(frame);
+    });
+}
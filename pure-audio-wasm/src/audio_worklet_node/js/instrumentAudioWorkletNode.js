@@ -1,21 +1,187 @@
-export class InstrumentAudioWorkletNode extends AudioWorkletNode {
-    noteOn(key, velocity) {
-        this.port.postMessage({
-            type: "noteOn",
-            data: {
-                key,
-                velocity
+// with static_linking this module is also evaluated in the AudioWorkletGlobalScope, which has no AudioWorkletNode
+const AudioWorkletNodeBase = globalThis.AudioWorkletNode ?? class {};
+
+// the processor posts the notifications of a block as one Float64Array, 4 numbers each: the type and its fields,
+// see message.rs
+const NOTIFICATION_LENGTH = 4;
+
+function decodeNotification([type, a, b, c]) {
+    switch (type) {
+        case 0:
+            return { type: "meter", output: a, channel: b, peak: c };
+        case 1:
+            return { type: "voiceCount", count: a };
+        case 2:
+            return { type: "custom", id: a, value: b };
+    }
+}
+
+// values are masked to the bits of their MIDI 1.0 field like the shared event queue does (midi::encode), so that the
+// processor sees the same events whichever way they are sent
+const data = value => value & 0x7F;
+const channelOf = channel => channel & 0x0F;
+
+// time: optional AudioContext time, omit to send as soon as possible
+// channel: MIDI channel (0-15)
+export class InstrumentAudioWorkletNode extends AudioWorkletNodeBase {
+    // the number of events the processor dropped or merged because its event queue was full, as of the last change
+    eventOverflowCount = 0;
+
+    constructor(context, name, options) {
+        super(context, name, options);
+        // notifications of the processor are dispatched as "notification" events, the detail is the notification.
+        // The processor also reports the overflow of its event queue
+        this.port.addEventListener("message", ({ data: message }) => {
+            if (message?.type === "notifications" && message.data instanceof Float64Array) {
+                for (let i = 0; i + NOTIFICATION_LENGTH <= message.data.length; i += NOTIFICATION_LENGTH) {
+                    const notification = decodeNotification(message.data.subarray(i, i + NOTIFICATION_LENGTH));
+                    if (notification) {
+                        this.dispatchEvent(new CustomEvent("notification", { detail: notification }));
+                    }
+                }
+            } else if (message?.type === "eventOverflow" && typeof message.data?.count === "number") {
+                this.eventOverflowCount = message.data.count;
             }
         });
+        this.port.start();
     }
 
-    noteOff(key, velocity) {
-        this.port.postMessage({
-            type: "noteOff",
-            data: {
-                key,
-                velocity
+    // events go through the shared memory queue set by the loader when the page is cross-origin isolated, and
+    // through the port when it isn't or the queue is full
+
+    noteOn(key, velocity, time, channel = 0) {
+        // a NoteOn without velocity is a NoteOff with the default release velocity, as in midi.rs
+        if (data(velocity) === 0) {
+            return this.noteOff(key, 64, time, channel);
+        }
+        [key, velocity, channel] = [data(key), data(velocity), channelOf(channel)];
+        if (!this.eventQueue?.note_on(channel, key, velocity, this.#toFrame(time))) {
+            this.#postEvent("noteOn", { channel, key, velocity, time });
+        }
+    }
+
+    noteOff(key, velocity, time, channel = 0) {
+        [key, velocity, channel] = [data(key), data(velocity), channelOf(channel)];
+        if (!this.eventQueue?.note_off(channel, key, velocity, this.#toFrame(time))) {
+            this.#postEvent("noteOff", { channel, key, velocity, time });
+        }
+    }
+
+    polyphonicKeyPressure(key, pressure, time, channel = 0) {
+        [key, pressure, channel] = [data(key), data(pressure), channelOf(channel)];
+        if (!this.eventQueue?.polyphonic_key_pressure(channel, key, pressure, this.#toFrame(time))) {
+            this.#postEvent("polyphonicKeyPressure", { channel, key, pressure, time });
+        }
+    }
+
+    controlChange(controller, value, time, channel = 0) {
+        [controller, value, channel] = [data(controller), data(value), channelOf(channel)];
+        if (!this.eventQueue?.control_change(channel, controller, value, this.#toFrame(time))) {
+            this.#postEvent("controlChange", { channel, controller, value, time });
+        }
+    }
+
+    programChange(program, time, channel = 0) {
+        [program, channel] = [data(program), channelOf(channel)];
+        if (!this.eventQueue?.program_change(channel, program, this.#toFrame(time))) {
+            this.#postEvent("programChange", { channel, program, time });
+        }
+    }
+
+    channelPressure(pressure, time, channel = 0) {
+        [pressure, channel] = [data(pressure), channelOf(channel)];
+        if (!this.eventQueue?.channel_pressure(channel, pressure, this.#toFrame(time))) {
+            this.#postEvent("channelPressure", { channel, pressure, time });
+        }
+    }
+
+    // value: 14-bit (0-16383), 8192 is the center
+    pitchBend(value, time, channel = 0) {
+        [value, channel] = [value & 0x3FFF, channelOf(channel)];
+        if (!this.eventQueue?.pitch_bend(channel, value, this.#toFrame(time))) {
+            this.#postEvent("pitchBend", { channel, value, time });
+        }
+    }
+
+    // bytes: raw MIDI 1.0 data, e.g. MIDIMessageEvent.data
+    midi(bytes, time) {
+        this.#postEvent("midi", { bytes, time });
+    }
+
+    // input: a Web MIDI MIDIInput, or anything dispatching "midimessage" events with data and timeStamp
+    // channels: MIDI channels (0-15) to forward channel messages of, all channels when omitted
+    // returns a function that detaches the input again
+    attachMidiInput(input, { channels } = {}) {
+        const forwarded = channels === undefined ? undefined : new Set(channels);
+        const listener = ({ data, timeStamp }) => {
+            const status = data[0];
+            // system messages carry no channel and aren't events
+            if (status === undefined || status < 0x80 || status >= 0xF0) {
+                return;
+            }
+            if (forwarded !== undefined && !forwarded.has(status & 0x0F)) {
+                return;
             }
+            this.#channelMessage(status, data[1], data[2], this.#toContextTime(timeStamp));
+        };
+        input.addEventListener("midimessage", listener);
+        // listening doesn't open the port implicitly like onmidimessage does
+        input.open?.();
+        return () => input.removeEventListener("midimessage", listener);
+    }
+
+    // decoded here rather than by the processor so that the messages go through the event queue, same as midi.rs
+    #channelMessage(status, a, b, time) {
+        const channel = status & 0x0F;
+        const length = (status & 0xF0) === 0xC0 || (status & 0xF0) === 0xD0 ? 1 : 2;
+        // truncated messages and stray status bytes are dropped
+        if (!(a <= 0x7F) || (length === 2 && !(b <= 0x7F))) {
+            return;
+        }
+        switch (status & 0xF0) {
+            case 0x80:
+                return this.noteOff(a, b, time, channel);
+            case 0x90:
+                return this.noteOn(a, b, time, channel);
+            case 0xA0:
+                return this.polyphonicKeyPressure(a, b, time, channel);
+            case 0xB0:
+                return this.controlChange(a, b, time, channel);
+            case 0xC0:
+                return this.programChange(a, time, channel);
+            case 0xD0:
+                return this.channelPressure(a, time, channel);
+            case 0xE0:
+                return this.pitchBend(a | (b << 7), time, channel);
+        }
+    }
+
+    // command: { type: "reset" } or { type: "custom", id, value }, delivered with the next block
+    command(command) {
+        this.#postEvent("command", command);
+    }
+
+    // performance.now() time to AudioContext time, as soon as possible when the context can't tell
+    #toContextTime(timeStamp) {
+        if (timeStamp === undefined || typeof this.context.getOutputTimestamp !== "function") {
+            return undefined;
+        }
+        const { contextTime, performanceTime } = this.context.getOutputTimestamp();
+        if (contextTime === undefined || performanceTime === undefined) {
+            return undefined;
+        }
+        return Math.max(0, contextTime + (timeStamp - performanceTime) / 1000);
+    }
+
+    // same as the processor, no time means as soon as possible
+    #toFrame(time) {
+        return time === undefined ? 0 : Math.round(time * this.context.sampleRate);
+    }
+
+    #postEvent(type, data) {
+        this.port.postMessage({
+            type,
+            data
         });
     }
-}
\ No newline at end of file
+}
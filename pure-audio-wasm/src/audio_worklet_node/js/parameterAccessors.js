@@ -0,0 +1,9 @@
+// accessors: [accessor, parameter name] pairs, e.g. ["volume", "Volume"] defines node.volume()
+export function defineParameterAccessors(node, accessors) {
+    for (const [accessor, name] of accessors) {
+        Object.defineProperty(node, accessor, {
+            value: () => node.parameters.get(name),
+            configurable: true,
+        });
+    }
+}
@@ -1,11 +1,31 @@
+use js_sys::{Array, Function, Object, Reflect};
 use wasm_bindgen::prelude::*;
-use web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, BaseAudioContext, EventTarget};
+use web_sys::{AudioWorkletNode, AudioWorkletNodeOptions, BaseAudioContext, EventTarget, MidiInput};
 
 #[wasm_bindgen(typescript_custom_section)]
-const TS_INSTRUMENT_AUDIO_WORKLET_NODE: &'static str = r#"
+const TS_INSTRUMENT_AUDIO_WORKLET_NODE: &str = r#"
+    export type Command =
+        | { type: "reset" }
+        | { type: "custom", id: number, value: number };
+
+    export type Notification =
+        | { type: "meter", output: number, channel: number, peak: number }
+        | { type: "voiceCount", count: number }
+        | { type: "custom", id: number, value: number };
+
     export class InstrumentAudioWorkletNode extends AudioWorkletNode {
-        noteOn(key: number, velocity: number): void;
-        noteOff(key: number, velocity: number): void;
+        noteOn(key: number, velocity: number, time?: number, channel?: number): void;
+        noteOff(key: number, velocity: number, time?: number, channel?: number): void;
+        polyphonicKeyPressure(key: number, pressure: number, time?: number, channel?: number): void;
+        controlChange(controller: number, value: number, time?: number, channel?: number): void;
+        programChange(program: number, time?: number, channel?: number): void;
+        channelPressure(pressure: number, time?: number, channel?: number): void;
+        pitchBend(value: number, time?: number, channel?: number): void;
+        midi(bytes: Uint8Array, time?: number): void;
+        command(command: Command): void;
+        attachMidiInput(input: MIDIInput, options?: { channels?: number[] }): () => void;
+        readonly eventOverflowCount: number;
+        // dispatches a CustomEvent<Notification> named "notification" for every notification of the processor
     }
     "#;
 
@@ -20,9 +40,34 @@ extern "C" {
         name: &str,
         options: &AudioWorkletNodeOptions,
     ) -> Result<InstrumentAudioWorkletNode, JsValue>;
+    /// The number of events the processor dropped or merged because its event queue was full, updated after the
+    /// block in which it changed.
+    #[wasm_bindgen(method, getter, js_class = "InstrumentAudioWorkletNode", js_name = eventOverflowCount)]
+    pub fn event_overflow_count(this: &InstrumentAudioWorkletNode) -> f64;
+    #[wasm_bindgen(method, js_class = "InstrumentAudioWorkletNode", js_name = attachMidiInput)]
+    fn attach_midi_input_with_options(this: &InstrumentAudioWorkletNode, input: &MidiInput, options: &Object) -> Function;
+}
+
+impl InstrumentAudioWorkletNode {
+    /// Forwards the channel messages of `input` as events, on `channels` (0-15) or on all channels when `None`. The
+    /// timestamps of the messages become the time of the events.
+    ///
+    /// Returns a function that detaches the input again.
+    pub fn attach_midi_input(&self, input: &MidiInput, channels: Option<&[u8]>) -> Function {
+        let options = Object::new();
+        if let Some(channels) = channels {
+            let channels: Array = channels.iter().map(|&channel| JsValue::from(channel)).collect();
+            // setting properties on a fresh object can't fail
+            let _ = Reflect::set(&options, &"channels".into(), &channels);
+        }
+        self.attach_midi_input_with_options(input, &options)
+    }
 }
 
-pub trait WasmAudioWorkletNode : Sized {
+pub trait WasmAudioWorkletNode : Sized + AsRef<JsValue> {
+    /// The name of the node type in TypeScript.
+    const TYPESCRIPT_TYPE: &'static str;
+
     fn new_with_options(
         context: &BaseAudioContext,
         name: &str,
@@ -31,6 +76,8 @@ pub trait WasmAudioWorkletNode : Sized {
 }
 
 impl WasmAudioWorkletNode for AudioWorkletNode {
+    const TYPESCRIPT_TYPE: &'static str = "AudioWorkletNode";
+
     fn new_with_options(
         context: &BaseAudioContext,
         name: &str,
@@ -41,6 +88,8 @@ impl WasmAudioWorkletNode for AudioWorkletNode {
 }
 
 impl WasmAudioWorkletNode for InstrumentAudioWorkletNode {
+    const TYPESCRIPT_TYPE: &'static str = "InstrumentAudioWorkletNode";
+
     fn new_with_options(
         context: &BaseAudioContext,
         name: &str,
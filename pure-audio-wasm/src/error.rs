@@ -0,0 +1,104 @@
+use js_sys::{Error, Reflect};
+use std::fmt::Display;
+use wasm_bindgen::{JsCast, JsValue};
+
+/// An error while loading a processor, surfaced to JavaScript as an `Error` named `LoaderError` whose `kind` is the
+/// variant in camel case, e.g. `httpStatus`, and whose `reason` is the message without context.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoaderError {
+    /// The request for the wasm module failed.
+    Fetch(String),
+    /// The server answered with a status other than 2xx.
+    HttpStatus(String),
+    /// The wasm module failed to compile.
+    Compile(String),
+    /// The worklet module failed to load or to register the processor.
+    AddModule(String),
+    /// The `AudioWorkletNode` couldn't be constructed.
+    CreateNode(String),
+    /// A parameter descriptor can't be registered as an `AudioParam`.
+    InvalidDescriptor(String),
+    /// The wasm source isn't a URL, `WebAssembly.Module` or buffer.
+    InvalidSource(String),
+}
+
+impl LoaderError {
+    /// The variant in camel case, the `kind` of the JavaScript error.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LoaderError::Fetch(_) => "fetch",
+            LoaderError::HttpStatus(_) => "httpStatus",
+            LoaderError::Compile(_) => "compile",
+            LoaderError::AddModule(_) => "addModule",
+            LoaderError::CreateNode(_) => "createNode",
+            LoaderError::InvalidDescriptor(_) => "invalidDescriptor",
+            LoaderError::InvalidSource(_) => "invalidSource",
+        }
+    }
+
+    /// The message without context, the `reason` of the JavaScript error.
+    pub fn message(&self) -> &str {
+        match self {
+            LoaderError::Fetch(message)
+            | LoaderError::HttpStatus(message)
+            | LoaderError::Compile(message)
+            | LoaderError::AddModule(message)
+            | LoaderError::CreateNode(message)
+            | LoaderError::InvalidDescriptor(message)
+            | LoaderError::InvalidSource(message) => message,
+        }
+    }
+
+    // recovers an error converted with `JsValue::from`, other values become `fallback`
+    #[cfg(not(feature = "static_linking"))]
+    pub(crate) fn from_js(value: &JsValue, fallback: fn(String) -> Self) -> Self {
+        let kind = Reflect::get(value, &"kind".into()).ok().and_then(|kind| kind.as_string());
+        let reason = Reflect::get(value, &"reason".into()).ok().and_then(|reason| reason.as_string());
+        let message = reason.unwrap_or_else(|| js_message(value));
+        match kind.as_deref() {
+            Some("fetch") => LoaderError::Fetch(message),
+            Some("httpStatus") => LoaderError::HttpStatus(message),
+            Some("compile") => LoaderError::Compile(message),
+            Some("addModule") => LoaderError::AddModule(message),
+            Some("createNode") => LoaderError::CreateNode(message),
+            Some("invalidDescriptor") => LoaderError::InvalidDescriptor(message),
+            Some("invalidSource") => LoaderError::InvalidSource(message),
+            _ => fallback(message),
+        }
+    }
+}
+
+impl Display for LoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoaderError::Fetch(message) => write!(f, "failed to fetch wasm module: {message}"),
+            LoaderError::HttpStatus(message) => write!(f, "failed to fetch wasm module: {message}"),
+            LoaderError::Compile(message) => write!(f, "failed to compile wasm module: {message}"),
+            LoaderError::AddModule(message) => write!(f, "failed to add worklet module: {message}"),
+            LoaderError::CreateNode(message) => write!(f, "failed to create node: {message}"),
+            LoaderError::InvalidDescriptor(message) => write!(f, "invalid parameter descriptor: {message}"),
+            LoaderError::InvalidSource(message) => write!(f, "invalid wasm source: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {}
+
+impl From<LoaderError> for JsValue {
+    fn from(error: LoaderError) -> Self {
+        let js_error = Error::new(&error.to_string());
+        js_error.set_name("LoaderError");
+        // setting properties on a fresh Error can't fail
+        let _ = Reflect::set(&js_error, &"kind".into(), &error.kind().into());
+        let _ = Reflect::set(&js_error, &"reason".into(), &error.message().into());
+        js_error.into()
+    }
+}
+
+// the message of a JS Error, or the value itself
+pub(crate) fn js_message(value: &JsValue) -> String {
+    match value.dyn_ref::<Error>() {
+        Some(error) => error.message().into(),
+        None => value.as_string().unwrap_or_else(|| format!("{value:?}")),
+    }
+}
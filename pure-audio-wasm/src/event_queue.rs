@@ -0,0 +1,114 @@
+use pure_audio::EventKind;
+
+/// The capacity of the event queue of a processor, unless set with
+/// [`WasmProcessor::with_event_queue`](crate::WasmProcessor::with_event_queue).
+pub const DEFAULT_EVENT_QUEUE_CAPACITY: usize = 512;
+
+/// What an [`EventQueue`] does with an event when it is full.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Keep the queued events, the new event is dropped.
+    #[default]
+    DropNewest,
+    /// Drop the queued event that is due first.
+    DropOldest,
+    /// Replace a queued event that the new one makes redundant: a change of the same controller, key pressure,
+    /// channel pressure, program or pitch bend on the same channel that is due no later than the new one. Otherwise
+    /// the new event is dropped.
+    MergeRedundant,
+}
+
+/// Pending events ordered by their absolute frame, with a fixed capacity so the audio thread never allocates.
+///
+/// Events on the same frame keep their order of arrival.
+#[derive(Clone, Debug)]
+pub struct EventQueue {
+    events: Vec<(u64, u8, EventKind)>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    overflow_count: u64,
+}
+
+impl EventQueue {
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            events: Vec::with_capacity(capacity),
+            capacity,
+            policy,
+            overflow_count: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// The number of events dropped or merged because the queue was full.
+    pub fn overflow_count(&self) -> u64 {
+        self.overflow_count
+    }
+
+    /// Queues an event, returns `false` when it was dropped.
+    pub fn push(&mut self, frame: u64, channel: u8, kind: EventKind) -> bool {
+        if self.events.len() >= self.capacity {
+            self.overflow_count += 1;
+            let dropped = match self.policy {
+                OverflowPolicy::DropNewest => None,
+                OverflowPolicy::DropOldest => (!self.events.is_empty()).then_some(0),
+                // a queued change that is due after the new one has the final say and stays
+                OverflowPolicy::MergeRedundant => self.events.iter().rposition(|&(queued_frame, queued_channel, queued)| {
+                    queued_frame <= frame && queued_channel == channel && supersedes(kind, queued)
+                }),
+            };
+            match dropped {
+                Some(index) => self.events.remove(index),
+                None => return false,
+            };
+        }
+
+        // after events of the same frame to keep their arrival order
+        let index = self.events.partition_point(|&(queued_frame, ..)| queued_frame <= frame);
+        self.events.insert(index, (frame, channel, kind));
+        true
+    }
+
+    /// Removes and returns the events before `end_frame`, in order.
+    pub fn drain_until(&mut self, end_frame: u64) -> impl Iterator<Item = (u64, u8, EventKind)> + '_ {
+        let count = self.events.partition_point(|&(frame, ..)| frame < end_frame);
+        self.events.drain(..count)
+    }
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_QUEUE_CAPACITY, OverflowPolicy::default())
+    }
+}
+
+// whether `new` overrides the state set by `queued`, on the same channel
+fn supersedes(new: EventKind, queued: EventKind) -> bool {
+    match (new, queued) {
+        (EventKind::ControlChange { controller, .. }, EventKind::ControlChange { controller: queued, .. }) => {
+            controller == queued
+        }
+        (EventKind::PolyphonicKeyPressure { key, .. }, EventKind::PolyphonicKeyPressure { key: queued, .. }) => {
+            key == queued
+        }
+        (EventKind::ChannelPressure { .. }, EventKind::ChannelPressure { .. })
+        | (EventKind::ProgramChange { .. }, EventKind::ProgramChange { .. })
+        | (EventKind::PitchBend { .. }, EventKind::PitchBend { .. }) => true,
+        _ => false,
+    }
+}
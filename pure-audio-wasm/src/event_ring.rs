@@ -0,0 +1,199 @@
+use js_sys::{Atomics, Int32Array, Reflect, SharedArrayBuffer};
+use pure_audio::{
+    midi::{self, MidiParser},
+    Event, EventKind,
+};
+use wasm_bindgen::prelude::*;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+// words: write index, read index, then the slots
+const HEAD: usize = 0;
+const TAIL: usize = 1;
+const HEADER_WORDS: usize = 2;
+// words per event: MIDI message, frame low, frame high
+const EVENT_WORDS: usize = 3;
+
+// events sent to an instrument node between two blocks
+const EVENT_QUEUE_CAPACITY: usize = 256;
+
+/// 32-bit words shared between the producer and the consumer of an [`EventRing`].
+///
+/// Loads must acquire and stores must release, so that an event is visible before the index that publishes it.
+pub trait RingStorage {
+    fn word_count(&self) -> usize;
+    fn load(&self, index: usize) -> u32;
+    fn store(&self, index: usize, value: u32);
+}
+
+impl RingStorage for [AtomicU32] {
+    fn word_count(&self) -> usize {
+        self.len()
+    }
+
+    fn load(&self, index: usize) -> u32 {
+        self[index].load(Ordering::Acquire)
+    }
+
+    fn store(&self, index: usize, value: u32) {
+        self[index].store(value, Ordering::Release)
+    }
+}
+
+/// A view on a `SharedArrayBuffer`, `Atomics` operations are sequentially consistent.
+impl RingStorage for Int32Array {
+    fn word_count(&self) -> usize {
+        self.length() as usize
+    }
+
+    fn load(&self, index: usize) -> u32 {
+        // can't fail on an Int32Array
+        Atomics::load(self, index as u32).unwrap_or_default() as u32
+    }
+
+    fn store(&self, index: usize, value: u32) {
+        let _ = Atomics::store(self, index as u32, value as i32);
+    }
+}
+
+impl<S: RingStorage + ?Sized> RingStorage for Arc<S> {
+    fn word_count(&self) -> usize {
+        (**self).word_count()
+    }
+
+    fn load(&self, index: usize) -> u32 {
+        (**self).load(index)
+    }
+
+    fn store(&self, index: usize, value: u32) {
+        (**self).store(index, value)
+    }
+}
+
+/// A lock-free single producer, single consumer queue of events with their absolute frame, in storage shared by both
+/// sides, e.g. a `SharedArrayBuffer` between the main thread and the audio thread.
+///
+/// Events are stored as MIDI messages, so a `NoteOn` with velocity 0 comes out as a `NoteOff`.
+pub struct EventRing<S> {
+    storage: S,
+    capacity: u32,
+}
+
+impl<S: RingStorage> EventRing<S> {
+    /// The number of words to store `capacity` events, rounded up to a power of two.
+    pub fn words(capacity: usize) -> usize {
+        HEADER_WORDS + capacity.next_power_of_two() * EVENT_WORDS
+    }
+
+    /// Both sides wrap the same storage, which starts out zeroed.
+    pub fn new(storage: S) -> Self {
+        let slots = storage.word_count().saturating_sub(HEADER_WORDS) / EVENT_WORDS;
+        // indices wrap around u32::MAX, so the capacity must divide 2^32
+        let capacity = match slots {
+            0 => 0,
+            slots => 1 << slots.ilog2().min(31),
+        };
+        Self { storage, capacity }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity as usize
+    }
+
+    /// Called by the producer, returns `false` when the queue is full.
+    pub fn push(&self, frame: u64, channel: u8, kind: EventKind) -> bool {
+        let head = self.storage.load(HEAD);
+        let tail = self.storage.load(TAIL);
+        if head.wrapping_sub(tail) >= self.capacity {
+            return false;
+        }
+
+        let mut message = [0; 3];
+        midi::encode(&Event::new(0, channel, kind), &mut message);
+        let [status, data1, data2] = message;
+        let slot = self.slot(head);
+        self.storage.store(slot, u32::from_le_bytes([status, data1, data2, 0]));
+        self.storage.store(slot + 1, frame as u32);
+        self.storage.store(slot + 2, (frame >> 32) as u32);
+        // publishes the event
+        self.storage.store(HEAD, head.wrapping_add(1));
+        true
+    }
+
+    /// Called by the consumer, returns the oldest event and its frame.
+    pub fn pop(&self) -> Option<(u64, u8, EventKind)> {
+        let tail = self.storage.load(TAIL);
+        if self.storage.load(HEAD) == tail {
+            return None;
+        }
+
+        let slot = self.slot(tail);
+        let message = self.storage.load(slot).to_le_bytes();
+        let frame = self.storage.load(slot + 1) as u64 | (self.storage.load(slot + 2) as u64) << 32;
+        // frees the slot
+        self.storage.store(TAIL, tail.wrapping_add(1));
+        let mut parser = MidiParser::new();
+        let event = parser.parse(&message[..3], 0).next()?;
+        Some((frame, event.channel, event.kind))
+    }
+
+    fn slot(&self, index: u32) -> usize {
+        HEADER_WORDS + (index & (self.capacity - 1)) as usize * EVENT_WORDS
+    }
+}
+
+// SharedArrayBuffer can only be shared with the audio thread when the page is cross-origin isolated
+pub(crate) fn create_event_queue_buffer() -> Option<SharedArrayBuffer> {
+    let cross_origin_isolated = Reflect::get(&js_sys::global(), &"crossOriginIsolated".into()).ok()?;
+    cross_origin_isolated
+        .is_truthy()
+        .then(|| SharedArrayBuffer::new((EventRing::<Int32Array>::words(EVENT_QUEUE_CAPACITY) * 4) as u32))
+}
+
+/// The main thread side of the event queue of an instrument node, used by `InstrumentAudioWorkletNode`.
+///
+/// `frame` is the absolute frame of the event. Returns `false` when the queue is full, the event is then posted to the
+/// port instead.
+#[wasm_bindgen]
+pub struct EventQueueWriter {
+    ring: EventRing<Int32Array>,
+}
+
+impl EventQueueWriter {
+    pub(crate) fn new(buffer: &SharedArrayBuffer) -> Self {
+        Self { ring: EventRing::new(Int32Array::new(buffer)) }
+    }
+}
+
+#[wasm_bindgen]
+impl EventQueueWriter {
+    pub fn note_on(&self, channel: u8, key: u8, velocity: u8, frame: f64) -> bool {
+        self.ring.push(frame as u64, channel, EventKind::NoteOn { key, velocity })
+    }
+
+    pub fn note_off(&self, channel: u8, key: u8, velocity: u8, frame: f64) -> bool {
+        self.ring.push(frame as u64, channel, EventKind::NoteOff { key, velocity })
+    }
+
+    pub fn polyphonic_key_pressure(&self, channel: u8, key: u8, pressure: u8, frame: f64) -> bool {
+        self.ring.push(frame as u64, channel, EventKind::PolyphonicKeyPressure { key, pressure })
+    }
+
+    pub fn control_change(&self, channel: u8, controller: u8, value: u8, frame: f64) -> bool {
+        self.ring.push(frame as u64, channel, EventKind::ControlChange { controller, value })
+    }
+
+    pub fn program_change(&self, channel: u8, program: u8, frame: f64) -> bool {
+        self.ring.push(frame as u64, channel, EventKind::ProgramChange { program })
+    }
+
+    pub fn channel_pressure(&self, channel: u8, pressure: u8, frame: f64) -> bool {
+        self.ring.push(frame as u64, channel, EventKind::ChannelPressure { pressure })
+    }
+
+    pub fn pitch_bend(&self, channel: u8, value: u16, frame: f64) -> bool {
+        self.ring.push(frame as u64, channel, EventKind::PitchBend { value })
+    }
+}
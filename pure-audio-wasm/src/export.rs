@@ -0,0 +1,253 @@
+/// Exports one or more processors from a single wasm module to JavaScript.
+///
+/// ```ignore
+/// export_processors! {
+///     Gain: gain::process,
+///     #[reserve_heap(1 << 20)]
+///     #[event_queue(1024, DropOldest)]
+///     Oscillator: oscillator::process,
+///     Chain: Graph::<2>::series(gain::process, filter::process),
+/// }
+/// ```
+///
+/// Processors are any expression implementing [`IntoWasmProcessor`](crate::IntoWasmProcessor), e.g. a pure function or
+/// a [`Graph`](pure_audio::graph::Graph), evaluated for every processor created.
+///
+/// generates
+/// - `create_wasm_processor(name, sampleRate)`, the factory called by the worklets, when the `build_processor` or
+///   `static_linking` feature of the calling crate is enabled
+/// - `create_gain_node(ctx, wasm, glue)`, or `create_gain_node(ctx)` with `static_linking`, which registers the
+///   processor and resolves to a `GainAudioWorkletNode`, which extends the node type of the processor, i.e.
+///   `AudioWorkletNode` or `InstrumentAudioWorkletNode` for instruments, and has a method per parameter, e.g.
+///   `volume()`, see [`parameter_accessors`](crate::parameter_accessors), and implements
+///   [`AudioParamAccess`](crate::AudioParamAccess) for the parameters of the processor
+/// - `register_all(ctx, wasm, glue)`, or `register_all(ctx)` with `static_linking`, which registers every processor up
+///   front
+/// - `typescript_definitions(module)`, outside of wasm, which declares the parameter accessors of the node types for
+///   TypeScript, see [`typescript_definitions`](crate::typescript_definitions)
+///
+/// Attributes configure every processor created in the worklet:
+/// - `#[reserve_heap(bytes)]` calls [`reserve_heap`](crate::reserve_heap)
+/// - `#[event_queue(capacity, policy)]` sets the capacity and [`OverflowPolicy`](crate::OverflowPolicy) of the
+///   pending events, see [`WasmProcessor::with_event_queue`](crate::WasmProcessor::with_event_queue)
+///
+/// `wasm` is the processor build as a URL, `WebAssembly.Module`, `ArrayBuffer` or typed array, see [`WasmSource`].
+/// `glue` is the URL of its wasm-bindgen glue, optional for a URL like `gain_bg.wasm` whose glue is `gain.js`, see
+/// [`WasmSource::glue_url`]. URLs and bytes are compiled once per context.
+/// Failures reject the returned promise with a [`LoaderError`](crate::LoaderError). The calling crate must depend on
+/// `wasm-bindgen` and `wasm-bindgen-futures`.
+#[macro_export]
+macro_rules! export_processors {
+    ($($(#[$attribute:ident $arguments:tt])* $name:ident: $process:expr),+ $(,)?) => {
+        // factory-method called from the constructor of the worklets
+        #[cfg(any(feature = "build_processor", feature = "static_linking"))]
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        pub fn create_wasm_processor(
+            name: &str,
+            sample_rate: f32,
+        ) -> Result<$crate::WasmProcessor, $crate::__private::JsValue> {
+            match name {
+                $(stringify!($name) => {
+                    let processor = $crate::create_wasm_processor($process, sample_rate);
+                    $(let processor = $crate::export_processors!(@$attribute processor, $arguments);)*
+                    Ok(processor)
+                })+
+                _ => Err(format!("unknown processor {name}").into()),
+            }
+        }
+
+        // user-called method to register all processors
+        #[cfg(not(any(feature = "build_processor", feature = "static_linking")))]
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        pub async fn register_all(
+            ctx: &$crate::__private::BaseAudioContext,
+            wasm: $crate::__private::JsValue,
+            glue: Option<String>,
+        ) -> Result<(), $crate::__private::JsValue> {
+            let source = $crate::WasmSource::try_from(wasm)?;
+            $($crate::register(stringify!($name), &source, glue.as_deref(), &$process, ctx).await?;)+
+            Ok(())
+        }
+
+        // user-called method to register all processors, the worklets run this same bundle
+        #[cfg(feature = "static_linking")]
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        pub async fn register_all(ctx: &$crate::__private::BaseAudioContext) -> Result<(), $crate::__private::JsValue> {
+            $($crate::register(stringify!($name), &$process, ctx).await?;)+
+            Ok(())
+        }
+
+        // writes the parameter accessors of the node types, e.g. from a native bin, see examples/gain-web
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn typescript_definitions(module: &str) -> Result<String, $crate::LoaderError> {
+            $crate::typescript_definitions(module, &[$((stringify!($name), &$crate::parameter_descriptors(&$process))),+])
+        }
+
+        $($crate::export_processors!(@node $name, $process);)+
+    };
+    (@reserve_heap $processor:ident, ($bytes:expr)) => {{
+        $crate::reserve_heap($bytes);
+        $processor
+    }};
+    (@event_queue $processor:ident, ($capacity:expr, $policy:ident)) => {
+        $processor.with_event_queue($capacity, $crate::OverflowPolicy::$policy)
+    };
+    // the accessors of the node types are declared by typescript_definitions(), in Rust every node type extends
+    // AudioWorkletNode as the type of a fn item can't be named
+    (@node_type $node:ident, $process:expr) => {
+        #[::wasm_bindgen::prelude::wasm_bindgen(typescript_custom_section)]
+        const _: &str = {
+            const INTERFACE: &$crate::__private::NodeInterface =
+                &$crate::__private::node_interface(stringify!($node), &|| $process);
+            INTERFACE.as_str()
+        };
+
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(extends = $crate::__private::AudioWorkletNode, typescript_type = $node)]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub type $node;
+        }
+
+        impl $crate::AudioParamAccess for $node {
+            fn parameter<P: $crate::__private::ProcessorParameter>(&self) -> $crate::__private::AudioParam {
+                const {
+                    let parameter = ::std::marker::PhantomData::<P>;
+                    assert!(
+                        $crate::__private::has_parameter(parameter, &|| $process),
+                        concat!("not a parameter of the processor of ", stringify!($node)),
+                    );
+                }
+                $crate::__private::audio_param::<P>(self.as_ref())
+            }
+        }
+    };
+    (@node $name:ident, $process:expr) => {
+        $crate::__private::paste! {
+            #[cfg(not(feature = "build_processor"))]
+            $crate::export_processors!(@node_type [<$name AudioWorkletNode>], $process);
+
+            // user-called method to create the node
+            #[cfg(not(any(feature = "build_processor", feature = "static_linking")))]
+            #[::wasm_bindgen::prelude::wasm_bindgen]
+            pub async fn [<create_ $name:snake _node>](
+                ctx: &$crate::__private::BaseAudioContext,
+                wasm: $crate::__private::JsValue,
+                glue: Option<String>,
+            ) -> Result<[<$name AudioWorkletNode>], $crate::__private::JsValue> {
+                let source = $crate::WasmSource::try_from(wasm)?;
+                let node = $crate::register_and_create_node(stringify!($name), source, glue.as_deref(), $process, ctx).await?;
+                Ok($crate::__private::JsCast::unchecked_into($crate::__private::JsValue::from(node)))
+            }
+
+            // user-called method to create the node, the worklet runs this same bundle
+            #[cfg(feature = "static_linking")]
+            #[::wasm_bindgen::prelude::wasm_bindgen]
+            pub async fn [<create_ $name:snake _node>](
+                ctx: &$crate::__private::BaseAudioContext,
+            ) -> Result<[<$name AudioWorkletNode>], $crate::__private::JsValue> {
+                let node = $crate::register_and_create_node(stringify!($name), $process, ctx).await?;
+                Ok($crate::__private::JsCast::unchecked_into($crate::__private::JsValue::from(node)))
+            }
+        }
+    };
+}
+
+/// Exports a single processor, see [`export_processors!`].
+///
+/// ```ignore
+/// export_processor!(Gain, gain::process);
+/// ```
+#[macro_export]
+macro_rules! export_processor {
+    ($name:ident, $process:expr) => {
+        $crate::export_processors!($name: $process);
+    };
+}
+
+// used by the expansion of export_processors!
+#[doc(hidden)]
+pub mod __private {
+    use crate::{IntoWasmProcessor, WasmAudioWorkletNode};
+    use pure_audio::HasParameter;
+    use std::marker::PhantomData;
+    pub use crate::typed_node::audio_param;
+    pub use paste::paste;
+    pub use pure_audio::ProcessorParameter;
+    pub use wasm_bindgen::{JsCast, JsValue};
+    pub use web_sys::{AudioParam, AudioWorkletNode, BaseAudioContext};
+
+    /// Whether `P` is a parameter of the processor returned by `process`.
+    pub const fn has_parameter<
+        P,
+        F,
+        Q,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const NUM_PARAMS: usize,
+        Params: HasParameter<P>,
+        S,
+    >(
+        _parameter: PhantomData<P>,
+        _process: &F,
+    ) -> bool
+    where
+        F: Fn() -> Q,
+        Q: IntoWasmProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, NUM_PARAMS, Params, S>,
+    {
+        Params::HAS_PARAMETER
+    }
+
+    /// A TypeScript interface built at compile time.
+    pub struct NodeInterface {
+        bytes: [u8; 256],
+        len: usize,
+    }
+
+    impl NodeInterface {
+        pub const fn as_str(&self) -> &str {
+            match std::str::from_utf8(self.bytes.split_at(self.len).0) {
+                Ok(interface) => interface,
+                Err(_) => panic!("the interface is built from strings"),
+            }
+        }
+    }
+
+    /// `export interface {node} extends ... {}`, extending the node type of the processor returned by `process`.
+    pub const fn node_interface<
+        F,
+        P,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const NUM_PARAMS: usize,
+        Params,
+        S,
+    >(
+        node: &str,
+        _process: &F,
+    ) -> NodeInterface
+    where
+        F: Fn() -> P,
+        P: IntoWasmProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, NUM_PARAMS, Params, S>,
+    {
+        let parts = ["export interface ", node, " extends ", P::AudioWorkletNodeType::TYPESCRIPT_TYPE, " {}"];
+        let mut interface = NodeInterface { bytes: [0; 256], len: 0 };
+        let mut part = 0;
+        while part < parts.len() {
+            let bytes = parts[part].as_bytes();
+            let mut byte = 0;
+            while byte < bytes.len() {
+                assert!(interface.len < interface.bytes.len(), "the node name is too long");
+                interface.bytes[interface.len] = bytes[byte];
+                interface.len += 1;
+                byte += 1;
+            }
+            part += 1;
+        }
+        interface
+    }
+}
@@ -1,12 +1,29 @@
 mod audio_worklet_node;
+mod error;
 mod es_module;
+mod event_queue;
+mod event_ring;
+mod export;
 mod loader;
+mod message;
 mod processor;
+mod typed_node;
+#[cfg(not(feature = "static_linking"))]
+mod wasm_source;
 
 // re-export
 pub use audio_worklet_node::*;
+pub use error::LoaderError;
+pub use event_queue::*;
+pub use event_ring::{EventQueueWriter, EventRing, RingStorage};
 pub use loader::*;
+pub use message::{on_notification, send_command};
 pub use processor::*;
+pub use typed_node::{parameter_accessors, parameter_descriptors, typescript_definitions, AudioParamAccess};
+#[cfg(not(feature = "static_linking"))]
+pub use wasm_source::*;
+#[doc(hidden)]
+pub use export::__private;
 
 // https://developer.mozilla.org/en-US/docs/Web/API/Web_Audio_API/Using_AudioWorklet#the_input_and_output_lists
 // currently fixed size
@@ -26,4 +43,13 @@ pub fn create_wasm_processor<
     sample_rate: f32,
 ) -> WasmProcessor {
     process.into_wasm_processor(sample_rate)
-}
\ No newline at end of file
+}
+
+/// Grows the heap up front so that allocating up to `bytes` doesn't grow the wasm memory, e.g. for the voices of an
+/// instrument. Growing the memory while processing is survived but slow.
+pub fn reserve_heap(bytes: usize) {
+    // allocated in small chunks that the allocator keeps after they're freed, linear memory never shrinks
+    const CHUNK_SIZE: usize = 64 * 1024;
+    let chunks: Vec<Vec<u8>> = (0..bytes.div_ceil(CHUNK_SIZE)).map(|_| Vec::with_capacity(CHUNK_SIZE)).collect();
+    std::hint::black_box(chunks);
+}
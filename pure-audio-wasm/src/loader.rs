@@ -1,16 +1,54 @@
-use crate::{audio_worklet_node::WasmAudioWorkletNode, es_module::{ImportMeta, IMPORT_META}, IntoWasmProcessor, PROCESSOR_BLOCK_LENGTH};
-use js_sys::{Array, Promise, Reflect, WebAssembly};
+use crate::{audio_worklet_node::WasmAudioWorkletNode, error::js_message, event_ring::create_event_queue_buffer, message::NOTIFICATION_LENGTH, parameter_accessors, typed_node::define_parameter_accessors, EventQueueWriter, IntoWasmProcessor, LoaderError, PROCESSOR_BLOCK_LENGTH};
+use js_sys::{Array, Reflect};
 use pure_audio::ParameterDescriptor;
-use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
+use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{console::log_1, AudioContext, AudioWorkletNodeOptions, Blob, BlobPropertyBag, Request, RequestInit, Url};
+use web_sys::{console::log_1, AudioWorkletNodeOptions, BaseAudioContext, ChannelCountMode, Blob, BlobPropertyBag, Url};
+#[cfg(not(feature = "static_linking"))]
+use crate::WasmSource;
+#[cfg(feature = "static_linking")]
+use crate::es_module::{ImportMeta, IMPORT_META};
 
-const AUDIO_CONTEXT_REGISTERED_MODULES_FIELD_NAME: &'static str = "registeredModules";
+const AUDIO_CONTEXT_REGISTERED_MODULES_FIELD_NAME: &str = "registeredModules";
 
+/// Registers the processor on first use and creates a node.
+///
+/// Without `static_linking` the worklet loads `source`, a separate build exporting `create_wasm_processor`, with its
+/// wasm-bindgen glue at `glue_url`. The glue URL may be omitted for a URL source named like the wasm-bindgen output,
+/// see [`WasmSource::glue_url`]. With `static_linking` the worklet runs this very bundle, which must export
+/// `create_wasm_processor` as well. See [`export_processors!`](crate::export_processors).
 pub async fn register_and_create_node<const IS_INSTRUMENT: bool, const NUM_INPUTS: usize, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const NUM_PARAMS: usize, Params, S, F>(name: &str, 
-    wasm_url: &str,
-    process: F, ctx: &AudioContext)
--> Result<F::AudioWorkletNodeType, JsValue>
+    #[cfg(not(feature = "static_linking"))] source: impl Into<WasmSource>,
+    #[cfg(not(feature = "static_linking"))] glue_url: Option<&str>,
+    process: F, ctx: &BaseAudioContext)
+-> Result<F::AudioWorkletNodeType, LoaderError>
+where
+    F: IntoWasmProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, NUM_PARAMS, Params, S>
+{
+    #[cfg(not(feature = "static_linking"))]
+    let source = source.into();
+    register(
+        name,
+        #[cfg(not(feature = "static_linking"))] &source,
+        #[cfg(not(feature = "static_linking"))] glue_url,
+        &process,
+        ctx
+    ).await?;
+
+    create_node(
+        name,
+        #[cfg(not(feature = "static_linking"))] &source,
+        &process,
+        ctx
+    ).await
+}
+
+/// Registers the processor with the context's audio worklet, unless it was registered before.
+pub async fn register<const IS_INSTRUMENT: bool, const NUM_INPUTS: usize, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const NUM_PARAMS: usize, Params, S, F>(name: &str,
+    #[cfg(not(feature = "static_linking"))] source: &WasmSource,
+    #[cfg(not(feature = "static_linking"))] glue_url: Option<&str>,
+    process: &F, ctx: &BaseAudioContext)
+-> Result<(), LoaderError>
 where
     F: IntoWasmProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, NUM_PARAMS, Params, S>
 {
@@ -20,34 +58,71 @@ where
             registered_modules
         } else {
             let registered_modules = Array::new();
-            Reflect::set(ctx, &AUDIO_CONTEXT_REGISTERED_MODULES_FIELD_NAME.into(), &registered_modules).unwrap_throw();
+            // contexts are extensible objects
+            let _ = Reflect::set(ctx, &AUDIO_CONTEXT_REGISTERED_MODULES_FIELD_NAME.into(), &registered_modules);
             registered_modules
         }
     };
 
-    if registered_modules.find(&mut |element, _, _| element == JsValue::from(name)).is_undefined() {
-        register_node(name, &process, ctx).await?;
+    if registered_modules.find(&mut |element, _, _| element == name).is_undefined() {
+        register_node(
+            name,
+            #[cfg(not(feature = "static_linking"))] source,
+            #[cfg(not(feature = "static_linking"))] glue_url,
+            process,
+            ctx
+        ).await?;
         registered_modules.push(&name.into());
     }
-
-    create_node(name, wasm_url, &process, ctx).await
+    Ok(())
 }
 
 async fn register_node<const IS_INSTRUMENT: bool, const NUM_INPUTS: usize, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const NUM_PARAMS: usize, F, Params, S>(
-    name: &str, _process: &F, ctx: &AudioContext) -> Result<(), JsValue>
+    name: &str,
+    #[cfg(not(feature = "static_linking"))] source: &WasmSource,
+    #[cfg(not(feature = "static_linking"))] glue_url: Option<&str>,
+    process: &F, ctx: &BaseAudioContext) -> Result<(), LoaderError>
 where
     F: IntoWasmProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, NUM_PARAMS, Params, S>
 {
     log_1(&"Registering node".into());
-    let meta_url: String = IMPORT_META.with(ImportMeta::url).into();
-    let mut parts = meta_url.split("/").collect::<Vec<_>>();
-    let name_lowercase = name.to_ascii_lowercase();
-    let bindgen_file = format!("{name_lowercase}.js");
-    log_1(&format!("Bindgen file: {bindgen_file}").into());
-    *parts.iter_mut().last().unwrap_throw() = &bindgen_file;
-    let meta_url = parts.join("/");
-    log_1(&format!("Meta url: {meta_url}").into());
+    // the worklet imports the glue of the processor build, or this very bundle with static_linking
+    #[cfg(not(feature = "static_linking"))]
+    let glue_url = source.glue_url(glue_url)?;
+    #[cfg(feature = "static_linking")]
+    let glue_url: String = IMPORT_META.with(ImportMeta::url).into();
+    log_1(&format!("Glue url: {glue_url}").into());
+
+    let code = worklet_module(name, &glue_url, process)?;
+
+    let add_module = async {
+        let options = BlobPropertyBag::new();
+        options.set_type("text/javascript");
+        let blob =
+            Blob::new_with_str_sequence_and_options(&Array::of1(&JsValue::from_str(&code)), &options)?;
+        let url = Url::create_object_url_with_blob(&blob)?;
+
+        log_1(&format!("Blob url: {url}").into());
+
+        JsFuture::from(ctx.audio_worklet()?.add_module(&url)?).await
+    };
+    add_module.await.map_err(|error| LoaderError::AddModule(format!("{name}: {}", js_message(&error))))?;
+    log_1(&"Added module".into());
+    Ok(())
+}
 
+/// The code of the worklet module registering processor `name`, as added by [`register`]. `glue_url` is the
+/// wasm-bindgen glue exporting `initSync` and `create_wasm_processor`.
+pub fn worklet_module<const IS_INSTRUMENT: bool, const NUM_INPUTS: usize, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const NUM_PARAMS: usize, F, Params, S>(
+    name: &str,
+    glue_url: &str,
+    _process: &F) -> Result<String, LoaderError>
+where
+    F: IntoWasmProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, NUM_PARAMS, Params, S>
+{
+    validate_descriptors(&F::get_parameter_descriptors())?;
+
+    // process() only copies into the views created by the constructor, it must not allocate
     let (process_condition, process_copy_input) = 
     if NUM_INPUTS == 0 {
         (
@@ -57,30 +132,43 @@ where
     } 
     else {    
         (
-            "if (inputs.every(i => i.length === 0) || outputs[0].length < 1) return true;",
-            (0..NUM_INPUTS)
-                .map(|i| {
-                    let offset = i * NUM_CHANNELS * PROCESSOR_BLOCK_LENGTH;
-                    format!("this.float32Memory.set(inputs[{i}][0] || new Float32Array({PROCESSOR_BLOCK_LENGTH}), this.inputsPtr + {offset});")
-                })
-                .collect::<Vec<_>>()
-                .join("\n")
+            r#"let connected = false;
+            for (let i = 0; i < inputs.length; i++) {
+                connected ||= inputs[i].length > 0;
+            }
+            if (!connected || outputs[0].length < 1) return true;"#,
+            // missing channels (disconnected inputs) are silent
+            format!(
+                r#"for (let i = 0; i < {NUM_INPUTS}; i++) {{
+                    const input = inputs[i];
+                    for (let c = 0; c < {NUM_CHANNELS}; c++) {{
+                        const view = this.inputViews[i * {NUM_CHANNELS} + c];
+                        const channel = input[c];
+                        if (channel) {{
+                            view.set(channel);
+                        }} else {{
+                            view.fill(0);
+                        }}
+                    }}
+                }}"#
+            )
         )
     };
 
-    let process_copy_output = 
-        (0..NUM_OUTPUTS)
-            .map(|i| {
-                let offset = i * NUM_CHANNELS * PROCESSOR_BLOCK_LENGTH;
-                format!("outputs[{i}][0].set(this.float32Memory.subarray(this.outputsPtr + {offset}, this.outputsPtr + {offset} + {PROCESSOR_BLOCK_LENGTH}));")
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
+    let process_copy_output = format!(
+        r#"for (let i = 0; i < {NUM_OUTPUTS}; i++) {{
+            const output = outputs[i];
+            for (let c = 0; c < output.length && c < {NUM_CHANNELS}; c++) {{
+                output[c].set(this.outputViews[i * {NUM_CHANNELS} + c]);
+            }}
+        }}"#
+    );
 
-    let (parameter_descriptors, parameter_values): (Vec<_>, Vec<_>) = 
+    let (parameter_descriptors, process_copy_parameters): (Vec<_>, Vec<_>) = 
         F::get_parameter_descriptors()
             .iter()
-            .map(|&ParameterDescriptor { name, default_value, min_value, max_value, automation_rate }| {
+            .enumerate()
+            .map(|(i, &ParameterDescriptor { name, default_value, min_value, max_value, automation_rate })| {
                 (format!(
                     r#"{{
                         name: '{name}',
@@ -90,50 +178,136 @@ where
                         automationRate: '{automation_rate}'
                     }}
                     "#
-                ), format!("parameters['{name}'][0]")) // assume k-rate parameters for now
+                ), format!(
+                    // a single value means the parameter is constant for the whole block
+                    r#"{{
+                        const values = parameters['{name}'];
+                        const view = this.parameterViews[{i}];
+                        if (values.length === 1) {{
+                            view.fill(values[0]);
+                        }} else {{
+                            view.set(values);
+                        }}
+                    }}"#
+                ))
             })
             .unzip();
 
-    let (parameter_descriptors, parameter_values) = 
-        (parameter_descriptors.join(", "), parameter_values.join(", "));
+    let (parameter_descriptors, process_copy_parameters) = 
+        (parameter_descriptors.join(", "), process_copy_parameters.join("\n"));
     
     // available global variables: sampleRate, currentTime, currentFrame
     // see https://developer.mozilla.org/en-US/docs/Web/API/AudioWorkletGlobalScope
-    let code = format!(
+    Ok(format!(
         r#"
-        import {{ initSync, create_wasm_processor }} from '{meta_url}';
+        import {{ initSync, create_wasm_processor }} from '{glue_url}';
 
         registerProcessor("{name}", class {name} extends AudioWorkletProcessor {{
             constructor(options) {{
                 // debugger;
                 super();
                 this.port.onmessage = msg => {{
-                    console.log("Audio thread received message: ");
-                    console.log(msg);
-                    this.port.postMessage("hello from audio thread!");
-                    if (msg.data.type === "noteOn") {{
-                        this.processor.note_on(msg.data.data.key, msg.data.data.velocity);
-                    }} else if (msg.data.type === "noteOff") {{
-                        this.processor.note_off(msg.data.data.key, msg.data.data.velocity);
+                    // messages other than {{ type, data: {{ ... }} }} are not meant for the processor
+                    const {{ type, data }} = msg.data ?? {{}};
+                    if (typeof data !== "object" || data === null) return;
+                    const frame = this.toFrame(data.time);
+                    switch (type) {{
+                        case "noteOn":
+                            this.processor.note_on(data.channel, data.key, data.velocity, frame);
+                            break;
+                        case "noteOff":
+                            this.processor.note_off(data.channel, data.key, data.velocity, frame);
+                            break;
+                        case "polyphonicKeyPressure":
+                            this.processor.polyphonic_key_pressure(data.channel, data.key, data.pressure, frame);
+                            break;
+                        case "controlChange":
+                            this.processor.control_change(data.channel, data.controller, data.value, frame);
+                            break;
+                        case "programChange":
+                            this.processor.program_change(data.channel, data.program, frame);
+                            break;
+                        case "channelPressure":
+                            this.processor.channel_pressure(data.channel, data.pressure, frame);
+                            break;
+                        case "pitchBend":
+                            this.processor.pitch_bend(data.channel, data.value, frame);
+                            break;
+                        case "midi":
+                            this.processor.push_midi(data.bytes, frame);
+                            break;
+                        case "command":
+                            this.processor.command(data);
+                            break;
                     }}
                 }};
-                const [module, sampleRate] = options.processorOptions;
+                const [module, sampleRate, eventRing] = options.processorOptions;
                 const {{ memory }} = initSync({{ module }});
-                this.processor = create_wasm_processor(sampleRate);
+                this.processor = create_wasm_processor("{name}", sampleRate);
+                if (eventRing) {{
+                    this.processor.set_event_ring(eventRing);
+                }}
 
                 this.inputsPtr = this.processor.get_inputs_ptr() / 4; // NUM_INPUTS * NUM_CHANNELS * [f32; 128]
                 this.outputsPtr = this.processor.get_outputs_ptr() / 4; // NUM_OUTPUTS * NUM_CHANNELS * [f32; 128]
-                this.parametersPtr = this.processor.get_parameters_ptr() / 4;
-                this.float32Memory = new Float32Array(memory.buffer);
+                this.parametersPtr = this.processor.get_parameters_ptr() / 4; // NUM_PARAMS * [f32; 128]
+                this.notificationsPtr = this.processor.get_notifications_ptr() / 8; // [f64; NOTIFICATION_LENGTH] per notification
+                this.memory = memory;
+                this.createViews();
+                this.eventOverflowCount = 0;
+            }}
+
+            // one view per block, created once and reused by process()
+            createViews() {{
+                this.float32Memory = new Float32Array(this.memory.buffer);
+                this.float64Memory = new Float64Array(this.memory.buffer);
+                this.inputViews = this.blockViews(this.inputsPtr, {NUM_INPUTS} * {NUM_CHANNELS});
+                this.outputViews = this.blockViews(this.outputsPtr, {NUM_OUTPUTS} * {NUM_CHANNELS});
+                this.parameterViews = this.blockViews(this.parametersPtr, {NUM_PARAMS});
+            }}
+
+            // growing the wasm memory detaches the buffer the views were created on
+            updateViews() {{
+                if (this.float32Memory.buffer !== this.memory.buffer) {{
+                    this.createViews();
+                }}
+            }}
+
+            blockViews(ptr, count) {{
+                const views = [];
+                for (let i = 0; i < count; i++) {{
+                    const offset = ptr + i * {PROCESSOR_BLOCK_LENGTH};
+                    views.push(this.float32Memory.subarray(offset, offset + {PROCESSOR_BLOCK_LENGTH}));
+                }}
+                return views;
+            }}
+
+            // AudioContext time to absolute frame, no time means as soon as possible
+            toFrame(time) {{
+                return time === undefined ? 0 : Math.round(time * sampleRate);
             }}
 
             process(inputs, outputs, parameters) {{
                 {process_condition}
+                this.updateViews();
                 {process_copy_input}
-                const flatParameters = [{parameter_values}];
-                this.float32Memory.set(new Float32Array(flatParameters), this.parametersPtr);
-                this.processor.process();
+                {process_copy_parameters}
+                this.processor.process(currentFrame);
+                // the processor may have grown the memory
+                this.updateViews();
                 {process_copy_output}
+                // the node keeps the number of events the full queue dropped or merged as eventOverflowCount
+                const eventOverflowCount = this.processor.event_overflow_count();
+                if (eventOverflowCount !== this.eventOverflowCount) {{
+                    this.eventOverflowCount = eventOverflowCount;
+                    this.port.postMessage({{ type: "eventOverflow", data: {{ count: eventOverflowCount }} }});
+                }}
+                // the notifications of the block leave the wasm memory as one message, see message.rs
+                const notificationCount = this.processor.encode_notifications();
+                if (notificationCount > 0) {{
+                    const data = this.float64Memory.slice(this.notificationsPtr, this.notificationsPtr + notificationCount * {NOTIFICATION_LENGTH});
+                    this.port.postMessage({{ type: "notifications", data }}, [data.buffer]);
+                }}
                 return true;
             }}
 
@@ -144,53 +318,71 @@ where
             }}     
         }});
     "#
-    );
-
-    let mut options = BlobPropertyBag::new();
-    options.type_("text/javascript");
-    let blob =
-        Blob::new_with_str_sequence_and_options(&Array::of1(&JsValue::from_str(&code)), &options)?;
-    let url = Url::create_object_url_with_blob(&blob)?;
-
-    log_1(&format!("Blob url: {url}").into());
+    ))
+}
 
-    JsFuture::from(ctx.audio_worklet()?.add_module(&url)?).await?;
-    log_1(&"Added module".into());
-    Ok(())
+/// Checks that `descriptors` can be embedded in the generated worklet code and registered as `AudioParam`s: names are
+/// unique and free of quotes, backslashes and line breaks, values are finite and defaults within their range, and each
+/// has an accessor, see [`parameter_accessors`].
+pub fn validate_descriptors(descriptors: &[ParameterDescriptor]) -> Result<(), LoaderError> {
+    for (i, &ParameterDescriptor { name, default_value, min_value, max_value, .. }) in descriptors.iter().enumerate() {
+        if name.is_empty() || name.contains(['\'', '"', '\\', '\n', '\r']) {
+            return Err(LoaderError::InvalidDescriptor(format!("{name:?} is not a valid parameter name")));
+        }
+        if descriptors[..i].iter().any(|descriptor| descriptor.name == name) {
+            return Err(LoaderError::InvalidDescriptor(format!("{name} is declared twice")));
+        }
+        if ![default_value, min_value, max_value].iter().all(|value| value.is_finite()) {
+            return Err(LoaderError::InvalidDescriptor(format!("{name} has a value that is not finite")));
+        }
+        if !(min_value <= default_value && default_value <= max_value) {
+            return Err(LoaderError::InvalidDescriptor(format!(
+                "{name} has default value {default_value} outside of {min_value}..={max_value}"
+            )));
+        }
+    }
+    parameter_accessors(descriptors).map(drop)
 }
 
 async fn create_node<const IS_INSTRUMENT: bool, const NUM_INPUTS: usize, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const NUM_PARAMS: usize, F, Params, S>(
     name: &str,
-    wasm_url: &str,
+    #[cfg(not(feature = "static_linking"))] source: &WasmSource,
     _process: &F,
-    ctx: &AudioContext)
--> Result<F::AudioWorkletNodeType, JsValue>
+    ctx: &BaseAudioContext)
+-> Result<F::AudioWorkletNodeType, LoaderError>
 where
     F: IntoWasmProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, NUM_PARAMS, Params, S>
 {
     log_1(&"Creating node".into());
-    let mut options = AudioWorkletNodeOptions::new();
-    let response = fetch(wasm_url).await.unwrap_throw();
-    log_1(&"Fetched wasm module".into());
-    let module = JsFuture::from(WebAssembly::compile_streaming(&response)).await.unwrap_throw();
-    log_1(&"Compiled wasm module".into());
-
-    options.number_of_inputs(NUM_INPUTS as u32);
-    options.number_of_outputs(NUM_OUTPUTS as u32);
-    options.processor_options(Some(
-        &Array::of2(&module, &ctx.sample_rate().into())
+    let options = AudioWorkletNodeOptions::new();
+    #[cfg(not(feature = "static_linking"))]
+    let module = source.compile(ctx).await?;
+    // the worklet instantiates the module this code runs in
+    #[cfg(feature = "static_linking")]
+    let module = wasm_bindgen::module();
+
+    options.set_number_of_inputs(NUM_INPUTS as u32);
+    options.set_number_of_outputs(NUM_OUTPUTS as u32);
+    // inputs are up- or downmixed to exactly NUM_CHANNELS
+    options.set_channel_count(NUM_CHANNELS as u32);
+    options.set_channel_count_mode(ChannelCountMode::Explicit);
+    options.set_output_channel_count(&(0..NUM_OUTPUTS).map(|_| JsValue::from(NUM_CHANNELS as u32)).collect::<Array>());
+    // instruments receive events through shared memory when possible, and through the port otherwise
+    let event_queue = if IS_INSTRUMENT { create_event_queue_buffer() } else { None };
+    options.set_processor_options(Some(
+        &Array::of3(&module, &ctx.sample_rate().into(), &event_queue.clone().into())
     ));
-    F::AudioWorkletNodeType::new_with_options(&ctx, name, &options)
+    let node = F::AudioWorkletNodeType::new_with_options(ctx, name, &options)
+        .map_err(|error| LoaderError::CreateNode(format!("{name}: {}", js_message(&error))))?;
+    if let Some(event_queue) = event_queue {
+        // nodes are extensible objects
+        let _ = Reflect::set(node.as_ref(), &"eventQueue".into(), &EventQueueWriter::new(&event_queue).into());
+    }
+    // node.volume() for the parameter Volume
+    let accessors: Array = parameter_accessors(&F::get_parameter_descriptors())?
+        .into_iter()
+        .map(|(accessor, descriptor)| Array::of2(&accessor.into(), &descriptor.name.into()))
+        .collect();
+    define_parameter_accessors(node.as_ref(), &accessors);
+    Ok(node)
 }
-
-async fn fetch(url: &str) -> Result<Promise, JsValue> {
-    let mut opts = RequestInit::new();
-    opts.method("GET");
-
-    log_1(&format!("Fetching {url}").into());
-
-    let request = Request::new_with_str_and_init(url, &opts)?;
-
-    let window = web_sys::window().unwrap_throw();
-    Ok(window.fetch_with_request(&request))
-}
\ No newline at end of file
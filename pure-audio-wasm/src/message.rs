@@ -0,0 +1,95 @@
+use js_sys::{Float64Array, Object, Reflect};
+use pure_audio::{Command, Notification};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{AudioWorkletNode, MessageEvent};
+
+// messages on the port of a node are `{ type, data }`, like the events of InstrumentAudioWorkletNode
+const COMMAND_MESSAGE_TYPE: &str = "command";
+const NOTIFICATION_MESSAGE_TYPE: &str = "notifications";
+
+/// Sends a command to the processor of `node`, it is delivered with the next block.
+pub fn send_command(node: &AudioWorkletNode, command: Command) -> Result<(), JsValue> {
+    node.port()?.post_message(&object(&[
+        ("type", COMMAND_MESSAGE_TYPE.into()),
+        ("data", command_to_js(command)),
+    ]))
+}
+
+/// Calls `callback` with every notification the processor of `node` sends, for as long as the node exists.
+pub fn on_notification(node: &AudioWorkletNode, mut callback: impl FnMut(Notification) + 'static) -> Result<(), JsValue> {
+    let port = node.port()?;
+    let listener = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let message = event.data();
+        if get(&message, "type").as_string().as_deref() == Some(NOTIFICATION_MESSAGE_TYPE) {
+            if let Some(encoded) = get(&message, "data").dyn_ref::<Float64Array>() {
+                encoded.to_vec().chunks_exact(NOTIFICATION_LENGTH).filter_map(decode_notification).for_each(&mut callback);
+            }
+        }
+    });
+    port.add_event_listener_with_callback("message", listener.as_ref().unchecked_ref())?;
+    // owned by the port from now on
+    listener.forget();
+    // listeners added with addEventListener only receive messages once the port is started
+    port.start();
+    Ok(())
+}
+
+pub(crate) fn command_to_js(command: Command) -> JsValue {
+    match command {
+        Command::Reset => object(&[("type", "reset".into())]),
+        Command::Custom { id, value } => object(&[("type", "custom".into()), ("id", id.into()), ("value", value.into())]),
+    }
+}
+
+pub(crate) fn command_from_js(value: &JsValue) -> Result<Command, JsValue> {
+    match get(value, "type").as_string().as_deref() {
+        Some("reset") => Ok(Command::Reset),
+        Some("custom") => Ok(Command::Custom { id: number(value, "id")? as u32, value: number(value, "value")? }),
+        _ => Err(format!("unknown command {value:?}").into()),
+    }
+}
+
+// the processor posts the notifications of a block as one Float64Array, NOTIFICATION_LENGTH numbers each: the type
+// and its fields. Decoded again by on_notification and instrumentAudioWorkletNode.js
+pub(crate) const NOTIFICATION_LENGTH: usize = 4;
+const METER: f64 = 0.0;
+const VOICE_COUNT: f64 = 1.0;
+const CUSTOM: f64 = 2.0;
+
+pub(crate) fn encode_notification(notification: Notification) -> [f64; NOTIFICATION_LENGTH] {
+    match notification {
+        Notification::Meter { output, channel, peak } => [METER, output as f64, channel as f64, peak.into()],
+        Notification::VoiceCount(count) => [VOICE_COUNT, count as f64, 0.0, 0.0],
+        Notification::Custom { id, value } => [CUSTOM, id.into(), value, 0.0],
+    }
+}
+
+pub(crate) fn decode_notification(encoded: &[f64]) -> Option<Notification> {
+    match *encoded {
+        [METER, output, channel, peak] => Some(Notification::Meter {
+            output: output as usize,
+            channel: channel as usize,
+            peak: peak as f32,
+        }),
+        [VOICE_COUNT, count, ..] => Some(Notification::VoiceCount(count as usize)),
+        [CUSTOM, id, value, _] => Some(Notification::Custom { id: id as u32, value }),
+        _ => None,
+    }
+}
+
+fn object(entries: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (key, value) in entries {
+        // setting properties on a fresh object can't fail
+        let _ = Reflect::set(&object, &(*key).into(), value);
+    }
+    object.into()
+}
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+    Reflect::get(value, &key.into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+fn number(value: &JsValue, key: &str) -> Result<f64, JsValue> {
+    get(value, key).as_f64().ok_or_else(|| format!("{key} of {value:?} is not a number").into())
+}
@@ -1,20 +1,37 @@
 use std::marker::PhantomData;
-use pure_audio::{Event, IntoProcessor, ParameterDescriptor, Processor};
+use js_sys::{Int32Array, SharedArrayBuffer};
+use pure_audio::{midi::MidiParser, CommandQueue, Event, EventKind, IntoProcessor, Messages, NotificationQueue, ParameterDescriptor, Processor};
 use wasm_bindgen::prelude::*;
 use web_sys::AudioWorkletNode;
-use crate::{InstrumentAudioWorkletNode, WasmAudioWorkletNode, PROCESSOR_BLOCK_LENGTH};
+use crate::{message::{command_from_js, encode_notification, NOTIFICATION_LENGTH}, EventQueue, EventRing, InstrumentAudioWorkletNode, OverflowPolicy, WasmAudioWorkletNode, PROCESSOR_BLOCK_LENGTH};
 
 #[wasm_bindgen]
 pub struct WasmProcessor {
-    implementation: Box<dyn WasmProcessorImplementation>
+    implementation: Box<dyn WasmProcessorImplementation>,
+    midi_parser: MidiParser,
+    // events written by the node on the main thread, when shared memory is available
+    event_ring: Option<EventRing<Int32Array>>,
+    // the notifications of the processed blocks, read by the worklet from the wasm memory
+    encoded_notifications: Vec<f64>
 }
 
 impl WasmProcessor {
-    fn new(implementation: Box<dyn WasmProcessorImplementation>) -> Self {
+    fn new(mut implementation: Box<dyn WasmProcessorImplementation>) -> Self {
+        let encoded_notifications = vec![0.0; implementation.notifications().capacity() * NOTIFICATION_LENGTH];
         Self {
-            implementation
+            implementation,
+            midi_parser: MidiParser::new(),
+            event_ring: None,
+            encoded_notifications
         }
     }
+
+    /// Replaces the queue of pending events, with room for `capacity` events that are due in the future or in the
+    /// current block. Events beyond that are handled according to `policy`.
+    pub fn with_event_queue(mut self, capacity: usize, policy: OverflowPolicy) -> Self {
+        self.implementation.set_pending_events(EventQueue::new(capacity, policy));
+        self
+    }
 }
 
 #[wasm_bindgen]
@@ -31,16 +48,92 @@ impl WasmProcessor {
         self.implementation.get_parameters_ptr()
     }
 
-    pub fn process(&mut self) {
-        self.implementation.process();
+    /// `current_frame` is the frame at the start of the block (`currentFrame` in the worklet scope)
+    pub fn process(&mut self, current_frame: f64) {
+        if let Some(event_ring) = &self.event_ring {
+            while let Some((frame, channel, kind)) = event_ring.pop() {
+                self.implementation.push_event(frame, channel, kind);
+            }
+        }
+        self.implementation.process(current_frame as u64);
+    }
+
+    /// the buffer of the node's [`EventQueueWriter`](crate::EventQueueWriter), drained before every block
+    pub fn set_event_ring(&mut self, buffer: &SharedArrayBuffer) {
+        self.event_ring = Some(EventRing::new(Int32Array::new(buffer)));
+    }
+
+    /// `frame` is the absolute frame of the event, frames in the past are delivered at the start of the next block.
+    /// `channel` is 0-15, higher bits are ignored. The other event methods take the same arguments.
+    pub fn note_on(&mut self, channel: u8, key: u8, velocity: u8, frame: f64) {
+        self.implementation.push_event(frame as u64, channel, EventKind::NoteOn { key, velocity });
+    }
+
+    pub fn note_off(&mut self, channel: u8, key: u8, velocity: u8, frame: f64) {
+        self.implementation.push_event(frame as u64, channel, EventKind::NoteOff { key, velocity });
+    }
+
+    pub fn polyphonic_key_pressure(&mut self, channel: u8, key: u8, pressure: u8, frame: f64) {
+        self.implementation.push_event(frame as u64, channel, EventKind::PolyphonicKeyPressure { key, pressure });
+    }
+
+    pub fn control_change(&mut self, channel: u8, controller: u8, value: u8, frame: f64) {
+        self.implementation.push_event(frame as u64, channel, EventKind::ControlChange { controller, value });
+    }
+
+    pub fn program_change(&mut self, channel: u8, program: u8, frame: f64) {
+        self.implementation.push_event(frame as u64, channel, EventKind::ProgramChange { program });
+    }
+
+    pub fn channel_pressure(&mut self, channel: u8, pressure: u8, frame: f64) {
+        self.implementation.push_event(frame as u64, channel, EventKind::ChannelPressure { pressure });
+    }
+
+    pub fn pitch_bend(&mut self, channel: u8, value: u16, frame: f64) {
+        self.implementation.push_event(frame as u64, channel, EventKind::PitchBend { value });
     }
 
-    pub fn note_on(&mut self, key: u8, velocity: u8) {
-        self.implementation.note_on(key, velocity);
+    /// raw MIDI 1.0 bytes, e.g. `MIDIMessageEvent.data`
+    pub fn push_midi(&mut self, bytes: &[u8], frame: f64) {
+        for Event { channel, kind, .. } in self.midi_parser.parse(bytes, 0) {
+            self.implementation.push_event(frame as u64, channel, kind);
+        }
+    }
+
+    /// a command posted to the port of the node, e.g. `{ type: "custom", id: 1, value: 0.5 }`
+    pub fn command(&mut self, command: JsValue) -> Result<(), JsValue> {
+        self.implementation.commands().push(command_from_js(&command)?);
+        Ok(())
+    }
+
+    /// the number of events dropped or merged because the event queue was full
+    pub fn event_overflow_count(&self) -> f64 {
+        self.implementation.pending_events().overflow_count() as f64
     }
 
-    pub fn note_off(&mut self, key: u8, velocity: u8) {
-        self.implementation.note_off(key, velocity);
+    /// the number of commands dropped because more were posted within a block than fit in the queue
+    pub fn command_drop_count(&mut self) -> f64 {
+        self.implementation.commands().dropped_count() as f64
+    }
+
+    /// the number of notifications dropped because more were sent than fit in the queue
+    pub fn notification_drop_count(&mut self) -> f64 {
+        self.implementation.notifications().dropped_count() as f64
+    }
+
+    pub fn get_notifications_ptr(&self) -> usize {
+        self.encoded_notifications.as_ptr() as usize
+    }
+
+    /// writes the notifications of the processed blocks to the buffer at `get_notifications_ptr`, empties the queue
+    /// and returns their number
+    pub fn encode_notifications(&mut self) -> usize {
+        let notifications = self.implementation.notifications();
+        let count = notifications.len();
+        for (notification, encoded) in notifications.drain().zip(self.encoded_notifications.chunks_exact_mut(NOTIFICATION_LENGTH)) {
+            encoded.copy_from_slice(&encode_notification(notification));
+        }
+        count
     }
 }
 
@@ -48,17 +141,23 @@ pub trait WasmProcessorImplementation: 'static {
     fn get_inputs_ptr(&mut self) -> usize;
     fn get_outputs_ptr(&self) -> usize;
     fn get_parameters_ptr(&mut self) -> usize;
-    fn process(&mut self);
-    fn note_on(&mut self, key: u8, velocity: u8);
-    fn note_off(&mut self, key: u8, velocity: u8);
+    fn process(&mut self, current_frame: u64);
+    fn push_event(&mut self, frame: u64, channel: u8, kind: EventKind);
+    fn pending_events(&self) -> &EventQueue;
+    fn set_pending_events(&mut self, pending_events: EventQueue);
+    fn commands(&mut self) -> &mut CommandQueue;
+    fn notifications(&mut self) -> &mut NotificationQueue;
 }
 
 struct WasmProcessorWrapper<P, const IS_INSTRUMENT: bool, const NUM_INPUTS: usize, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const NUM_PARAMS: usize, Params> {
     processor: P,
+    pending_events: EventQueue,
     events: Vec<Event>,
+    commands: CommandQueue,
+    notifications: NotificationQueue,
     inputs: [[[f32; PROCESSOR_BLOCK_LENGTH]; NUM_CHANNELS]; NUM_INPUTS],
     outputs: [[[f32; PROCESSOR_BLOCK_LENGTH]; NUM_CHANNELS]; NUM_OUTPUTS],
-    parameters: [f32; NUM_PARAMS],
+    parameters: [[f32; PROCESSOR_BLOCK_LENGTH]; NUM_PARAMS],
     marker: PhantomData<Params>
 }
 
@@ -67,10 +166,14 @@ impl<P, const IS_INSTRUMENT: bool, const NUM_INPUTS: usize, const NUM_OUTPUTS: u
     fn new(processor: P) -> Self {
         Self {
             processor,
-            events: vec![],
+            // reserved up front, so the audio thread doesn't allocate in steady state
+            pending_events: EventQueue::default(),
+            events: Vec::with_capacity(EventQueue::default().capacity()),
+            commands: CommandQueue::default(),
+            notifications: NotificationQueue::default(),
             inputs: [[[0.0; PROCESSOR_BLOCK_LENGTH]; NUM_CHANNELS]; NUM_INPUTS],
             outputs: [[[0.0; PROCESSOR_BLOCK_LENGTH]; NUM_CHANNELS]; NUM_OUTPUTS],
-            parameters: [0.0; NUM_PARAMS],
+            parameters: [[0.0; PROCESSOR_BLOCK_LENGTH]; NUM_PARAMS],
             marker: PhantomData
         }
     }
@@ -120,19 +223,42 @@ where
         self.parameters.as_ptr() as *const _ as usize
     }
 
-    fn process(&mut self) {
+    fn process(&mut self, current_frame: u64) {
+        // move the events that fall within this block, late events are delivered at offset 0
+        let end_frame = current_frame + PROCESSOR_BLOCK_LENGTH as u64;
+        self.events.extend(self.pending_events.drain_until(end_frame).map(|(frame, channel, kind)| {
+            Event::new(frame.saturating_sub(current_frame) as usize, channel, kind)
+        }));
+
         // clear outputs
-        self.outputs = [[[0.0; PROCESSOR_BLOCK_LENGTH]; NUM_CHANNELS]; NUM_OUTPUTS];
-        self.processor.process(&self.inputs, &mut self.outputs, &self.parameters, &self.events);
+        self.outputs.iter_mut().flatten().for_each(|channel| channel.fill(0.0));
+        let mut messages = Messages::new(self.commands.as_slice(), &mut self.notifications);
+        self.processor.process(&self.inputs, &mut self.outputs, &self.parameters, &self.events, &mut messages);
         self.events.clear();
+        self.commands.clear();
+    }
+
+    fn push_event(&mut self, frame: u64, channel: u8, kind: EventKind) {
+        // channels are 4 bits, as in midi::encode
+        self.pending_events.push(frame, channel & 0x0F, kind);
+    }
+
+    fn pending_events(&self) -> &EventQueue {
+        &self.pending_events
+    }
+
+    fn set_pending_events(&mut self, pending_events: EventQueue) {
+        // a block holds at most all pending events
+        self.events.reserve(pending_events.capacity());
+        self.pending_events = pending_events;
     }
 
-    fn note_on(&mut self, key: u8, velocity: u8) {
-        self.events.push(Event::NoteOn { key, velocity });
+    fn commands(&mut self) -> &mut CommandQueue {
+        &mut self.commands
     }
 
-    fn note_off(&mut self, key: u8, velocity: u8) {
-        self.events.push(Event::NoteOff { key, velocity });
+    fn notifications(&mut self) -> &mut NotificationQueue {
+        &mut self.notifications
     }
 }
 
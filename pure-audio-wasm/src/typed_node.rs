@@ -0,0 +1,170 @@
+use crate::{IntoWasmProcessor, LoaderError};
+use pure_audio::{ParameterDescriptor, ProcessorParameter};
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+use web_sys::{AudioParam, AudioWorkletNode};
+
+// members of the nodes that an accessor must not shadow
+const NODE_MEMBERS: &[&str] = &[
+    // Object
+    "constructor", "hasOwnProperty", "isPrototypeOf", "propertyIsEnumerable", "toLocaleString", "toString", "valueOf",
+    // EventTarget
+    "addEventListener", "dispatchEvent", "removeEventListener",
+    // AudioNode
+    "channelCount", "channelCountMode", "channelInterpretation", "connect", "context", "disconnect", "numberOfInputs",
+    "numberOfOutputs",
+    // AudioWorkletNode
+    "onprocessorerror", "parameters", "port",
+    // InstrumentAudioWorkletNode
+    "attachMidiInput", "channelPressure", "command", "controlChange", "eventOverflowCount", "eventQueue", "midi",
+    "noteOff", "noteOn",
+    "pitchBend", "polyphonicKeyPressure", "programChange",
+];
+
+#[wasm_bindgen(module = "/src/audio_worklet_node/js/parameterAccessors.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = defineParameterAccessors)]
+    pub(crate) fn define_parameter_accessors(node: &JsValue, accessors: &js_sys::Array);
+}
+
+/// Typed access to the `AudioParam`s of the node types generated by [`export_processors!`](crate::export_processors),
+/// keyed by the parameter types of their processor, so that renaming a parameter can't leave a stale name behind.
+///
+/// ```ignore
+/// let volume = node.parameter::<GainVolumeParameter>();
+/// ```
+///
+/// A parameter type that the processor doesn't take fails to build. It is checked when the call is compiled to code,
+/// not by `cargo check`:
+///
+/// ```compile_fail
+/// use pure_audio::{EffectAudioData, ParameterAutomationRate, ParameterDescriptor, ProcessorParameter};
+/// use pure_audio_wasm::AudioParamAccess;
+///
+/// struct Volume(f32);
+///
+/// impl ProcessorParameter for Volume {
+///     const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+///         automation_rate: ParameterAutomationRate::K,
+///         default_value: 1.0,
+///         max_value: 1.0,
+///         min_value: 0.0,
+///         name: "Volume",
+///     };
+///
+///     fn from_parameter(value: f32) -> Self {
+///         Volume(value)
+///     }
+/// }
+///
+/// fn silence(_: EffectAudioData<1, 1, 1, 128>) {}
+///
+/// pure_audio_wasm::export_processor!(Silence, silence);
+///
+/// fn main() {
+///     let _: fn(&SilenceAudioWorkletNode) -> web_sys::AudioParam = AudioParamAccess::parameter::<Volume>;
+/// }
+/// ```
+pub trait AudioParamAccess {
+    /// The `AudioParam` described by `P`, the first one when a graph takes `P` more than once.
+    fn parameter<P: ProcessorParameter>(&self) -> AudioParam;
+}
+
+// used by the node types of export_processors!, which are created with the parameters of their processor
+#[doc(hidden)]
+pub fn audio_param<P: ProcessorParameter>(node: &AudioWorkletNode) -> AudioParam {
+    let parameters = node.parameters().unwrap_throw();
+    parameters.get(P::DESCRIPTOR.name).expect_throw("nodes have the parameters of their processor")
+}
+
+/// The accessor methods defined on the nodes with the parameters they return, e.g. `volume()` for `Volume`.
+///
+/// Accessors are the names in lower camel case. Fails with [`LoaderError::InvalidDescriptor`] when an accessor isn't an
+/// identifier, would shadow a member of the node or repeats another accessor.
+pub fn parameter_accessors(
+    descriptors: &[ParameterDescriptor],
+) -> Result<Vec<(String, &ParameterDescriptor)>, LoaderError> {
+    let mut accessors: Vec<(String, &ParameterDescriptor)> = Vec::with_capacity(descriptors.len());
+    for descriptor in descriptors {
+        let name = descriptor.name;
+        let accessor = accessor_name(name);
+        let invalid = |message| Err(LoaderError::InvalidDescriptor(message));
+        // identifiers can't start with a digit
+        if !accessor.starts_with(|c: char| !c.is_ascii_digit()) {
+            return invalid(format!("{name} has accessor {accessor:?}, which is not an identifier"));
+        }
+        if NODE_MEMBERS.contains(&accessor.as_str()) {
+            return invalid(format!("{name} has accessor {accessor}, a member of the node"));
+        }
+        if let Some((_, other)) = accessors.iter().find(|(existing, _)| *existing == accessor) {
+            return invalid(format!("{name} has accessor {accessor}, as does {}", other.name));
+        }
+        accessors.push((accessor, descriptor));
+    }
+    Ok(accessors)
+}
+
+// "Volume" becomes volume, "Cutoff frequency" cutoffFrequency and "LFO rate" lfoRate
+fn accessor_name(name: &str) -> String {
+    let mut accessor = String::new();
+    for word in name.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+        let mut chars = word.chars();
+        if accessor.is_empty() {
+            if word.chars().any(char::is_lowercase) {
+                accessor.extend(chars.next().into_iter().flat_map(char::to_lowercase));
+                accessor.push_str(chars.as_str());
+            } else {
+                accessor.extend(word.chars().flat_map(char::to_lowercase));
+            }
+        } else {
+            accessor.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+            accessor.push_str(chars.as_str());
+        }
+    }
+    accessor
+}
+
+/// The parameter descriptors of `process`.
+pub fn parameter_descriptors<
+    const IS_INSTRUMENT: bool,
+    const NUM_INPUTS: usize,
+    const NUM_OUTPUTS: usize,
+    const NUM_CHANNELS: usize,
+    const NUM_PARAMS: usize,
+    Params,
+    S,
+    F: IntoWasmProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, NUM_PARAMS, Params, S>,
+>(
+    _process: &F,
+) -> [ParameterDescriptor; NUM_PARAMS] {
+    F::get_parameter_descriptors()
+}
+
+/// TypeScript declarations adding the parameter accessors to the node types generated by
+/// [`export_processors!`](crate::export_processors), e.g. `volume(): AudioParam` on `GainAudioWorkletNode`.
+///
+/// `module` is the specifier of the wasm-bindgen typings the declarations augment, e.g. `"./gain_loader"`, and `nodes`
+/// the processor names with their descriptors. Fails when the accessors of a node do, see [`parameter_accessors`].
+pub fn typescript_definitions(module: &str, nodes: &[(&str, &[ParameterDescriptor])]) -> Result<String, LoaderError> {
+    let mut definitions = format!(
+        "// generated from the parameter descriptors, regenerate after changing them\n\
+        export {{}};\n\
+        \n\
+        declare module \"{module}\" {{\n"
+    );
+    for (name, descriptors) in nodes {
+        let _ = writeln!(definitions, "    interface {name}AudioWorkletNode {{");
+        for (accessor, ParameterDescriptor { name: parameter, default_value, min_value, max_value, automation_rate }) in
+            parameter_accessors(descriptors)?
+        {
+            let _ = writeln!(
+                definitions,
+                "        /** `{parameter}`, {min_value} to {max_value}, {default_value} by default, {automation_rate} */\n        \
+                {accessor}(): AudioParam;"
+            );
+        }
+        definitions.push_str("    }\n");
+    }
+    definitions.push_str("}\n");
+    Ok(definitions)
+}
@@ -0,0 +1,181 @@
+use js_sys::{ArrayBuffer, Map, Promise, Reflect, Uint8Array, WebAssembly};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use wasm_bindgen::{prelude::*, JsCast};
+use crate::{error::js_message, LoaderError};
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{console::log_1, BaseAudioContext, Request, Response};
+
+const AUDIO_CONTEXT_COMPILED_MODULES_FIELD_NAME: &str = "compiledModules";
+
+#[wasm_bindgen]
+extern "C" {
+    // the global fetch, available on Window and WorkerGlobalScope alike
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_request(request: &Request) -> Promise;
+}
+
+/// The wasm module of the processor build.
+#[derive(Clone, Debug)]
+pub enum WasmSource {
+    /// An already compiled module.
+    Module(WebAssembly::Module),
+    /// The bytes of a module, compiled once per context.
+    Bytes(Vec<u8>),
+    /// A URL, fetched and compiled once per context.
+    Url(String),
+}
+
+impl WasmSource {
+    /// The absolute URL of the wasm-bindgen glue the worklet imports, `glue_url` when given and otherwise the one
+    /// wasm-bindgen writes next to a URL source, `gain.js` for `gain_bg.wasm`.
+    pub fn glue_url(&self, glue_url: Option<&str>) -> Result<String, LoaderError> {
+        match (glue_url, self) {
+            (Some(glue_url), _) => absolute_url(glue_url),
+            (None, WasmSource::Url(url)) => match absolute_url(url)?.strip_suffix("_bg.wasm") {
+                Some(stem) => Ok(format!("{stem}.js")),
+                None => Err(LoaderError::InvalidSource(format!(
+                    "no glue URL given and {url} isn't named like a wasm-bindgen output (*_bg.wasm)"
+                ))),
+            },
+            (None, _) => Err(LoaderError::InvalidSource("a module or bytes need the URL of their glue".into())),
+        }
+    }
+
+    /// Compiles the module, URLs and bytes once per context.
+    pub async fn compile(&self, ctx: &BaseAudioContext) -> Result<WebAssembly::Module, LoaderError> {
+        match self {
+            WasmSource::Module(module) => Ok(module.clone()),
+            WasmSource::Bytes(bytes) => {
+                // keyed by their contents, which no absolute URL matches as it can't contain spaces
+                let mut hasher = DefaultHasher::new();
+                bytes.hash(&mut hasher);
+                let key = format!("{} bytes hashing to {:016x}", bytes.len(), hasher.finish());
+                compile_once(ctx, &key, || future_to_promise(compile_bytes(Uint8Array::from(&bytes[..])))).await
+            }
+            WasmSource::Url(url) => {
+                let url = absolute_url(url)?;
+                compile_once(ctx, &url, || future_to_promise(fetch_and_compile(url.clone()))).await
+            }
+        }
+    }
+}
+
+// the pending compilation is cached, so concurrent nodes share it
+async fn compile_once(
+    ctx: &BaseAudioContext,
+    key: &str,
+    compile: impl FnOnce() -> Promise,
+) -> Result<WebAssembly::Module, LoaderError> {
+    let compiled_modules = compiled_modules(ctx);
+    let key = JsValue::from_str(key);
+    let compilation = match compiled_modules.get(&key).dyn_into::<Promise>() {
+        Ok(compilation) => compilation,
+        Err(_) => {
+            let compilation = compile();
+            compiled_modules.set(&key, &compilation);
+            compilation
+        }
+    };
+    JsFuture::from(compilation)
+        .await
+        .map(JsCast::unchecked_into)
+        .map_err(|error| {
+            // allow a retry
+            compiled_modules.delete(&key);
+            LoaderError::from_js(&error, LoaderError::Compile)
+        })
+}
+
+async fn compile_bytes(bytes: Uint8Array) -> Result<JsValue, JsValue> {
+    JsFuture::from(WebAssembly::compile(&bytes))
+        .await
+        .map_err(|error| LoaderError::Compile(js_message(&error)).into())
+}
+
+async fn fetch_and_compile(url: String) -> Result<JsValue, JsValue> {
+    log_1(&format!("Fetching {url}").into());
+    let request = Request::new_with_str(&url).map_err(|error| LoaderError::InvalidSource(js_message(&error)))?;
+    let response: Response = JsFuture::from(fetch_with_request(&request))
+        .await
+        .map_err(|error| LoaderError::Fetch(format!("{url}: {}", js_message(&error))))?
+        .unchecked_into();
+    if !response.ok() {
+        return Err(LoaderError::HttpStatus(format!("{url}: {} {}", response.status(), response.status_text())).into());
+    }
+    let module = JsFuture::from(WebAssembly::compile_streaming(&Promise::resolve(&response)))
+        .await
+        .map_err(|error| LoaderError::Compile(format!("{url}: {}", js_message(&error))))?;
+    Ok(module)
+}
+
+fn absolute_url(url: &str) -> Result<String, LoaderError> {
+    Request::new_with_str(url)
+        .map(|request| request.url())
+        .map_err(|error| LoaderError::InvalidSource(js_message(&error)))
+}
+
+fn compiled_modules(ctx: &BaseAudioContext) -> Map {
+    match Reflect::get(ctx, &AUDIO_CONTEXT_COMPILED_MODULES_FIELD_NAME.into()).and_then(JsCast::dyn_into::<Map>) {
+        Ok(compiled_modules) => compiled_modules,
+        Err(_) => {
+            let compiled_modules = Map::new();
+            // contexts are extensible objects
+            let _ = Reflect::set(ctx, &AUDIO_CONTEXT_COMPILED_MODULES_FIELD_NAME.into(), &compiled_modules);
+            compiled_modules
+        }
+    }
+}
+
+impl From<&str> for WasmSource {
+    fn from(url: &str) -> Self {
+        WasmSource::Url(url.to_string())
+    }
+}
+
+impl From<String> for WasmSource {
+    fn from(url: String) -> Self {
+        WasmSource::Url(url)
+    }
+}
+
+impl From<Vec<u8>> for WasmSource {
+    fn from(bytes: Vec<u8>) -> Self {
+        WasmSource::Bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for WasmSource {
+    fn from(bytes: &[u8]) -> Self {
+        WasmSource::Bytes(bytes.to_vec())
+    }
+}
+
+impl From<WebAssembly::Module> for WasmSource {
+    fn from(module: WebAssembly::Module) -> Self {
+        WasmSource::Module(module)
+    }
+}
+
+/// Accepts a URL string, a `WebAssembly.Module`, an `ArrayBuffer` or a typed array.
+impl TryFrom<JsValue> for WasmSource {
+    type Error = LoaderError;
+
+    fn try_from(value: JsValue) -> Result<Self, Self::Error> {
+        if let Some(url) = value.as_string() {
+            Ok(WasmSource::Url(url))
+        } else if value.is_instance_of::<WebAssembly::Module>() {
+            Ok(WasmSource::Module(value.unchecked_into()))
+        } else if value.is_instance_of::<ArrayBuffer>() {
+            Ok(WasmSource::Bytes(Uint8Array::new(&value).to_vec()))
+        } else if ArrayBuffer::is_view(&value) {
+            // the bytes viewed, whatever the element type
+            let get = |key: &str| Reflect::get(&value, &key.into()).map_err(|error| LoaderError::InvalidSource(js_message(&error)));
+            let byte_offset = get("byteOffset")?.as_f64().unwrap_or_default() as u32;
+            let byte_length = get("byteLength")?.as_f64().unwrap_or_default() as u32;
+            let buffer = get("buffer")?;
+            Ok(WasmSource::Bytes(Uint8Array::new_with_byte_offset_and_length(&buffer, byte_offset, byte_length).to_vec()))
+        } else {
+            Err(LoaderError::InvalidSource("expected a URL, WebAssembly.Module, ArrayBuffer or typed array".into()))
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use pure_audio::EventKind;
+use pure_audio_wasm::{EventQueue, OverflowPolicy};
+
+const NOTE_ON: EventKind = EventKind::NoteOn { key: 60, velocity: 100 };
+
+fn cc(value: u8) -> EventKind {
+    EventKind::ControlChange { controller: 64, value }
+}
+
+fn full(policy: OverflowPolicy) -> EventQueue {
+    let mut queue = EventQueue::new(3, policy);
+    assert!(queue.push(20, 0, cc(127)));
+    assert!(queue.push(10, 0, NOTE_ON));
+    assert!(queue.push(30, 1, cc(127)));
+    queue
+}
+
+#[test]
+fn events_are_ordered_by_frame_then_arrival() {
+    let mut queue = EventQueue::new(8, OverflowPolicy::DropNewest);
+    queue.push(128, 0, cc(1));
+    queue.push(5, 0, cc(2));
+    queue.push(128, 0, cc(3));
+    queue.push(0, 0, cc(4));
+
+    assert_eq!(queue.drain_until(128).collect::<Vec<_>>(), [(0, 0, cc(4)), (5, 0, cc(2))]);
+    assert_eq!(queue.drain_until(256).collect::<Vec<_>>(), [(128, 0, cc(1)), (128, 0, cc(3))]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn drop_newest_keeps_the_queue() {
+    let mut queue = full(OverflowPolicy::DropNewest);
+    assert!(!queue.push(0, 0, cc(0)));
+    assert_eq!(queue.overflow_count(), 1);
+    assert_eq!(
+        queue.drain_until(u64::MAX).collect::<Vec<_>>(),
+        [(10, 0, NOTE_ON), (20, 0, cc(127)), (30, 1, cc(127))]
+    );
+}
+
+#[test]
+fn drop_oldest_makes_room() {
+    let mut queue = full(OverflowPolicy::DropOldest);
+    assert!(queue.push(40, 0, cc(0)));
+    assert!(queue.push(50, 0, cc(1)));
+    assert_eq!(queue.overflow_count(), 2);
+    assert_eq!(queue.drain_until(u64::MAX).collect::<Vec<_>>(), [(30, 1, cc(127)), (40, 0, cc(0)), (50, 0, cc(1))]);
+}
+
+#[test]
+fn merge_redundant_replaces_a_change_on_the_same_channel() {
+    let mut queue = full(OverflowPolicy::MergeRedundant);
+    // the release of the sustain pedal of channel 0 replaces the queued press
+    assert!(queue.push(25, 0, cc(0)));
+    // nothing to merge a note with
+    assert!(!queue.push(26, 0, NOTE_ON));
+    assert_eq!(queue.overflow_count(), 2);
+    assert_eq!(queue.len(), queue.capacity());
+    assert_eq!(queue.drain_until(u64::MAX).collect::<Vec<_>>(), [(10, 0, NOTE_ON), (25, 0, cc(0)), (30, 1, cc(127))]);
+}
+
+#[test]
+fn merge_redundant_keeps_a_change_that_is_due_later() {
+    let mut queue = full(OverflowPolicy::MergeRedundant);
+    // the queued change at frame 20 overrides the new one at 15 anyway
+    assert!(!queue.push(15, 0, cc(0)));
+    assert_eq!(queue.overflow_count(), 1);
+    assert_eq!(
+        queue.drain_until(u64::MAX).collect::<Vec<_>>(),
+        [(10, 0, NOTE_ON), (20, 0, cc(127)), (30, 1, cc(127))]
+    );
+}
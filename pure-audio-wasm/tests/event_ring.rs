@@ -0,0 +1,82 @@
+use pure_audio::EventKind;
+use pure_audio_wasm::{EventRing, RingStorage};
+use std::{
+    sync::{atomic::AtomicU32, Arc},
+    thread,
+};
+
+fn storage(capacity: usize) -> Arc<[AtomicU32]> {
+    (0..EventRing::<Arc<[AtomicU32]>>::words(capacity)).map(|_| AtomicU32::new(0)).collect()
+}
+
+fn note(i: u64) -> (u64, u8, EventKind) {
+    (i * 3, (i % 16) as u8, EventKind::NoteOn { key: (i % 128) as u8, velocity: 1 + (i % 127) as u8 })
+}
+
+#[test]
+fn events_round_trip_until_full() {
+    let ring = EventRing::new(storage(4));
+    assert_eq!(ring.capacity(), 4);
+
+    let events = [
+        (0, 0, EventKind::NoteOn { key: 60, velocity: 100 }),
+        (u64::MAX, 15, EventKind::PitchBend { value: 16383 }),
+        (1 << 40, 3, EventKind::ProgramChange { program: 7 }),
+        (128, 9, EventKind::NoteOff { key: 60, velocity: 0 }),
+    ];
+    for (frame, channel, kind) in events {
+        assert!(ring.push(frame, channel, kind));
+    }
+    assert!(!ring.push(0, 0, EventKind::ChannelPressure { pressure: 1 }));
+
+    for event in events {
+        assert_eq!(ring.pop(), Some(event));
+    }
+    assert_eq!(ring.pop(), None);
+}
+
+#[test]
+fn indices_wrap_around() {
+    let storage = storage(8);
+    // start just before the indices overflow
+    storage.store(0, u32::MAX - 2);
+    storage.store(1, u32::MAX - 2);
+    let ring = EventRing::new(storage);
+    let push = |(frame, channel, kind)| ring.push(frame, channel, kind);
+
+    for i in (0..100).step_by(2) {
+        assert!(push(note(i)) && push(note(i + 1)));
+        assert_eq!(ring.pop(), Some(note(i)));
+        assert_eq!(ring.pop(), Some(note(i + 1)));
+    }
+}
+
+#[test]
+fn producer_and_consumer_threads_see_every_event_in_order() {
+    const COUNT: u64 = 100_000;
+    let storage = storage(64);
+    let producer = EventRing::new(storage.clone());
+    let consumer = EventRing::new(storage);
+
+    let producing = thread::spawn(move || {
+        for i in 0..COUNT {
+            let (frame, channel, kind) = note(i);
+            while !producer.push(frame, channel, kind) {
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut received = 0;
+    while received < COUNT {
+        match consumer.pop() {
+            Some(event) => {
+                assert_eq!(event, note(received));
+                received += 1;
+            }
+            None => thread::yield_now(),
+        }
+    }
+    producing.join().unwrap();
+    assert_eq!(consumer.pop(), None);
+}
@@ -0,0 +1,61 @@
+use pure_audio::{
+    graph::Graph, EffectAudioData, InputBuffer, OutputBuffer, ParameterAutomationRate, ParameterDescriptor,
+    ProcessorParameter,
+};
+use pure_audio_wasm::AudioParamAccess;
+use web_sys::AudioParam;
+
+#[derive(Copy, Clone)]
+struct Scale(f32);
+
+impl ProcessorParameter for Scale {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::K,
+        default_value: 1.0,
+        max_value: 4.0,
+        min_value: 0.0,
+        name: "Scale",
+    };
+
+    fn from_parameter(value: f32) -> Self {
+        Scale(value)
+    }
+}
+
+fn scale(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData<1, 1, 1, 128>,
+    Scale(scale): Scale,
+) {
+    for (input_sample, output_sample) in input.iter().zip(output) {
+        *output_sample = input_sample * scale;
+    }
+}
+
+mod exported {
+    use super::*;
+
+    pure_audio_wasm::export_processors! {
+        Scale: scale,
+        Chain: Graph::<2>::series(scale, scale),
+    }
+}
+
+#[test]
+fn processors_are_exported_from_expressions() {
+    let definitions = exported::typescript_definitions("./chain").unwrap();
+    assert!(definitions.contains("interface ScaleAudioWorkletNode {\n        /** `Scale`"));
+    assert!(definitions.contains("interface ChainAudioWorkletNode {\n        /** `Scale`"));
+    assert!(definitions.contains("/** `Scale 2`, 0 to 4, 1 by default, k-rate */\n        scale2(): AudioParam;"));
+}
+
+#[test]
+fn node_types_access_the_parameters_of_their_processor() {
+    // instantiated to check the parameter at compile time, nodes only exist in the browser
+    let scale: fn(&exported::ScaleAudioWorkletNode) -> AudioParam = AudioParamAccess::parameter::<Scale>;
+    let chain: fn(&exported::ChainAudioWorkletNode) -> AudioParam = AudioParamAccess::parameter::<Scale>;
+    std::hint::black_box((scale, chain));
+}
@@ -0,0 +1,232 @@
+// Runs the node glue without a browser: `node --test pure-audio-wasm/tests/js`
+import { test } from "node:test";
+import assert from "node:assert/strict";
+
+// stands in for the Web Audio node, records what is posted to the processor
+globalThis.AudioWorkletNode = class extends EventTarget {
+    constructor(context) {
+        super();
+        this.context = context;
+        this.posted = [];
+        const listeners = [];
+        this.port = {
+            postMessage: message => this.posted.push(message),
+            addEventListener: (type, listener) => listeners.push(listener),
+            start() {},
+            // delivers a message from the processor
+            receive: data => listeners.forEach(listener => listener({ data })),
+        };
+    }
+};
+
+const { InstrumentAudioWorkletNode } = await import("../../src/audio_worklet_node/js/instrumentAudioWorkletNode.js");
+
+// a MIDIInput that delivers messages on demand
+class FakeMidiInput {
+    listeners = new Set();
+    opened = false;
+
+    addEventListener(type, listener) {
+        assert.equal(type, "midimessage");
+        this.listeners.add(listener);
+    }
+
+    removeEventListener(type, listener) {
+        this.listeners.delete(listener);
+    }
+
+    open() {
+        this.opened = true;
+        return Promise.resolve(this);
+    }
+
+    receive(bytes, timeStamp) {
+        for (const listener of this.listeners) {
+            listener({ data: new Uint8Array(bytes), timeStamp });
+        }
+    }
+}
+
+// the context output 2 s of audio at performance time 5000 ms
+const context = {
+    sampleRate: 48000,
+    getOutputTimestamp: () => ({ contextTime: 2, performanceTime: 5000 }),
+};
+
+// the channels of the events posted to the processor
+const postedChannels = node => node.posted.map(({ data }) => data.channel);
+
+test("messages are forwarded with their time in the context", () => {
+    const node = new InstrumentAudioWorkletNode(context);
+    const input = new FakeMidiInput();
+    node.attachMidiInput(input);
+    assert.ok(input.opened);
+
+    input.receive([0x90, 60, 100], 5250);
+    input.receive([0x80, 60, 0], 5500);
+
+    assert.deepEqual(node.posted, [
+        { type: "noteOn", data: { channel: 0, key: 60, velocity: 100, time: 2.25 } },
+        { type: "noteOff", data: { channel: 0, key: 60, velocity: 0, time: 2.5 } },
+    ]);
+});
+
+test("only the given channels are forwarded and system messages are skipped", () => {
+    const node = new InstrumentAudioWorkletNode(context);
+    const input = new FakeMidiInput();
+    node.attachMidiInput(input, { channels: [0, 9] });
+
+    input.receive([0x90, 60, 100], 5000);
+    input.receive([0x91, 61, 100], 5000);
+    input.receive([0x99, 36, 127], 5000);
+    input.receive([0xF8], 5000);
+    input.receive([0xF0, 0x7E, 0xF7], 5000);
+
+    assert.deepEqual(postedChannels(node), [0, 9]);
+});
+
+test("several inputs can be attached and detached", () => {
+    const node = new InstrumentAudioWorkletNode(context);
+    const keyboard = new FakeMidiInput();
+    const pads = new FakeMidiInput();
+    const detachKeyboard = node.attachMidiInput(keyboard);
+    node.attachMidiInput(pads);
+
+    keyboard.receive([0x90, 60, 100], 5000);
+    pads.receive([0x99, 36, 127], 5000);
+    detachKeyboard();
+    keyboard.receive([0x80, 60, 0], 5000);
+
+    assert.equal(keyboard.listeners.size, 0);
+    assert.deepEqual(postedChannels(node), [0, 9]);
+});
+
+test("messages are sent as soon as possible when the context has no output timestamp", () => {
+    const node = new InstrumentAudioWorkletNode({ sampleRate: 44100 });
+    const input = new FakeMidiInput();
+    node.attachMidiInput(input);
+
+    input.receive([0xE0, 0, 64], 1234);
+
+    assert.deepEqual(node.posted, [{ type: "pitchBend", data: { channel: 0, value: 8192, time: undefined } }]);
+});
+
+test("channel messages are decoded and malformed ones dropped", () => {
+    const node = new InstrumentAudioWorkletNode(context);
+    const input = new FakeMidiInput();
+    node.attachMidiInput(input);
+
+    input.receive([0x93, 60, 0], 5000);
+    input.receive([0xA1, 60, 20], 5000);
+    input.receive([0xB2, 7, 90], 5000);
+    input.receive([0xC3, 5], 5000);
+    input.receive([0xD4, 30], 5000);
+    input.receive([0xE5, 0x7F, 0x7F], 5000);
+    // truncated, data byte out of range and running status
+    input.receive([0x90, 60], 5000);
+    input.receive([0xB0, 7, 0x80], 5000);
+    input.receive([60, 100], 5000);
+
+    assert.deepEqual(node.posted, [
+        { type: "noteOff", data: { channel: 3, key: 60, velocity: 64, time: 2 } },
+        { type: "polyphonicKeyPressure", data: { channel: 1, key: 60, pressure: 20, time: 2 } },
+        { type: "controlChange", data: { channel: 2, controller: 7, value: 90, time: 2 } },
+        { type: "programChange", data: { channel: 3, program: 5, time: 2 } },
+        { type: "channelPressure", data: { channel: 4, pressure: 30, time: 2 } },
+        { type: "pitchBend", data: { channel: 5, value: 16383, time: 2 } },
+    ]);
+});
+
+test("MIDI input goes through the shared event queue when there is one", () => {
+    const node = new InstrumentAudioWorkletNode(context);
+    const written = [];
+    node.eventQueue = {
+        note_on: (...args) => written.push(["note_on", ...args]),
+        control_change: () => false,
+    };
+    const input = new FakeMidiInput();
+    node.attachMidiInput(input);
+
+    input.receive([0x92, 60, 100], 5250);
+    // a full queue falls back to the port
+    input.receive([0xB0, 7, 90], 5000);
+
+    assert.deepEqual(written, [["note_on", 2, 60, 100, 108000]]);
+    assert.deepEqual(node.posted, [{ type: "controlChange", data: { channel: 0, controller: 7, value: 90, time: 2 } }]);
+});
+
+test("notes go through the shared event queue when there is one", () => {
+    const node = new InstrumentAudioWorkletNode(context);
+    const written = [];
+    node.eventQueue = { note_on: (...args) => written.push(args) };
+
+    node.noteOn(60, 100, 1.5, 2);
+
+    assert.deepEqual(written, [[2, 60, 100, 72000]]);
+    assert.deepEqual(node.posted, []);
+
+    // a full queue falls back to the port
+    node.eventQueue = { note_on: () => false };
+    node.noteOn(60, 100, 1.5, 2);
+    assert.deepEqual(node.posted, [{ type: "noteOn", data: { channel: 2, key: 60, velocity: 100, time: 1.5 } }]);
+});
+
+test("events are masked the same way whichever way they are sent", () => {
+    const send = node => {
+        node.noteOn(0x80 + 60, 0x100 + 100, 1.5, 0x10 + 2);
+        node.noteOn(60, 0x80, 1.5, 2);
+        node.controlChange(0x80 + 7, 0x80 + 90, 1.5, 0x10 + 2);
+        node.pitchBend(0x4000 + 8192, 1.5, 2);
+    };
+    // the arguments of the events written to the queue and posted to the port, without the time
+    const queued = [];
+    const record = type => (channel, ...args) => queued.push([type, channel, ...args.slice(0, -1)]);
+    const shared = new InstrumentAudioWorkletNode(context);
+    shared.eventQueue = {
+        note_on: record("noteOn"),
+        note_off: record("noteOff"),
+        control_change: record("controlChange"),
+        pitch_bend: record("pitchBend"),
+    };
+    send(shared);
+    const port = new InstrumentAudioWorkletNode(context);
+    send(port);
+    const posted = port.posted.map(({ type, data: { channel, time, ...values } }) => [type, channel, ...Object.values(values)]);
+
+    assert.deepEqual(posted, queued);
+    assert.deepEqual(posted, [
+        ["noteOn", 2, 60, 100],
+        // a NoteOn with a velocity of 0 once masked is a NoteOff
+        ["noteOff", 2, 60, 64],
+        ["controlChange", 2, 7, 90],
+        ["pitchBend", 2, 8192],
+    ]);
+});
+
+test("notifications of a block are dispatched one by one and other messages are ignored", () => {
+    const node = new InstrumentAudioWorkletNode(context);
+    const notifications = [];
+    node.addEventListener("notification", ({ detail }) => notifications.push(detail));
+
+    for (const data of [null, undefined, 5, "notifications", { type: "notifications" }, { type: "notifications", data: [1, 2, 0, 0] }]) {
+        node.port.receive(data);
+    }
+    assert.deepEqual(notifications, []);
+
+    node.port.receive({ type: "notifications", data: new Float64Array([0, 1, 2, 0.5, 1, 3, 0, 0, 2, 7, 1.5, 0, 9, 0, 0, 0]) });
+    assert.deepEqual(notifications, [
+        { type: "meter", output: 1, channel: 2, peak: 0.5 },
+        { type: "voiceCount", count: 3 },
+        { type: "custom", id: 7, value: 1.5 },
+    ]);
+});
+
+test("the overflow count of the processor's event queue is kept", () => {
+    const node = new InstrumentAudioWorkletNode(context);
+    assert.equal(node.eventOverflowCount, 0);
+    node.port.receive({ type: "eventOverflow", data: { count: 4 } });
+    assert.equal(node.eventOverflowCount, 4);
+    node.port.receive({ type: "eventOverflow", data: null });
+    node.port.receive({ type: "eventOverflow" });
+    assert.equal(node.eventOverflowCount, 4);
+});
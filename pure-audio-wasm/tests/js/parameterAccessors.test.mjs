@@ -0,0 +1,12 @@
+import { test } from "node:test";
+import assert from "node:assert/strict";
+import { defineParameterAccessors } from "../../src/audio_worklet_node/js/parameterAccessors.js";
+
+test("accessors return the AudioParam of their parameter", () => {
+    const volume = {}, cutoff = {};
+    const node = { parameters: new Map([["Volume", volume], ["Cutoff frequency", cutoff]]) };
+    defineParameterAccessors(node, [["volume", "Volume"], ["cutoffFrequency", "Cutoff frequency"]]);
+    assert.equal(node.volume(), volume);
+    assert.equal(node.cutoffFrequency(), cutoff);
+    assert.ok(!Object.keys(node).includes("volume"));
+});
@@ -0,0 +1,65 @@
+// stands in for the wasm-bindgen glue of a processor build: a gain on every channel of one input and output
+const BLOCK = 128;
+const Float32 = Float32Array;
+
+let memory;
+
+export function initSync() {
+    memory = { buffer: new ArrayBuffer(64 * 1024) };
+    return { memory };
+}
+
+export function create_wasm_processor(name, sampleRate) {
+    return new FakeProcessor(memory, globalThis.numberOfChannels);
+}
+
+class FakeProcessor {
+    inputsPtr = 1024;
+
+    constructor(memory, channels) {
+        this.memory = memory;
+        this.channels = channels;
+        this.outputsPtr = this.inputsPtr + channels * BLOCK * 4;
+        this.parametersPtr = this.outputsPtr + channels * BLOCK * 4;
+        this.notificationsPtr = this.parametersPtr + BLOCK * 4;
+        // set by the tests
+        this.growOnProcess = false;
+        this.notifications = [];
+        this.eventOverflowCount = 0;
+        this.frames = [];
+        this.events = [];
+        this.commands = [];
+    }
+
+    get_inputs_ptr() { return this.inputsPtr; }
+    get_outputs_ptr() { return this.outputsPtr; }
+    get_parameters_ptr() { return this.parametersPtr; }
+    get_notifications_ptr() { return this.notificationsPtr; }
+
+    process(currentFrame) {
+        this.frames.push(currentFrame);
+        if (this.growOnProcess) {
+            // the old buffer is detached when wasm memory grows
+            const grown = new ArrayBuffer(this.memory.buffer.byteLength * 2);
+            new Uint8Array(grown).set(new Uint8Array(this.memory.buffer));
+            this.memory.buffer = grown;
+        }
+        const memory = new Float32(this.memory.buffer);
+        const [inputs, outputs, volume] = [this.inputsPtr / 4, this.outputsPtr / 4, this.parametersPtr / 4];
+        for (let i = 0; i < this.channels * BLOCK; i++) {
+            memory[outputs + i] = memory[inputs + i] * memory[volume + (i % BLOCK)];
+        }
+    }
+
+    note_on(channel, key, velocity, frame) { this.events.push({ channel, key, velocity, frame }); }
+    command(command) { this.commands.push(command); }
+    event_overflow_count() { return this.eventOverflowCount; }
+
+    // the notifications set by the tests are already encoded, 4 numbers each
+    encode_notifications() {
+        const count = this.notifications.length / 4;
+        new Float64Array(this.memory.buffer).set(this.notifications, this.notificationsPtr / 8);
+        this.notifications = [];
+        return count;
+    }
+}
@@ -0,0 +1,159 @@
+// Runs a generated worklet module against fakeGlue.mjs: `node harness.mjs <module> <channels>`, see tests/worklet.rs
+import { test } from "node:test";
+import assert from "node:assert/strict";
+
+const [modulePath, channels] = process.argv.slice(2);
+const BLOCK = 128;
+
+globalThis.numberOfChannels = Number(channels);
+globalThis.sampleRate = 48000;
+globalThis.currentFrame = 0;
+globalThis.AudioWorkletProcessor = class {
+    constructor() {
+        this.posted = [];
+        this.port = { onmessage: null, postMessage: (message, transfer = []) => this.posted.push({ message, transfer }) };
+    }
+};
+const processors = new Map();
+globalThis.registerProcessor = (name, processor) => processors.set(name, processor);
+
+await import(modulePath);
+assert.equal(processors.size, 1);
+const [Processor] = processors.values();
+
+function create() {
+    return new Processor({ processorOptions: [{}, sampleRate, undefined] });
+}
+
+function block(value) {
+    return Array.from({ length: numberOfChannels }, (_, c) => Float32Array.from({ length: BLOCK }, (_, i) => value * (c + 1) + i));
+}
+
+function outputs() {
+    return [Array.from({ length: numberOfChannels }, () => new Float32Array(BLOCK))];
+}
+
+function run(processor, input, parameters) {
+    const output = outputs();
+    assert.equal(processor.process([input], output, parameters), true);
+    currentFrame += BLOCK;
+    return output[0];
+}
+
+// counts the calls of `methods` of `target` while `f` runs
+function counting(target, methods, f) {
+    let count = 0;
+    const originals = methods.map(method => [method, target[method]]);
+    for (const [method, original] of originals) {
+        target[method] = function (...args) {
+            count++;
+            return original.apply(this, args);
+        };
+    }
+    try {
+        f();
+    } finally {
+        for (const [method, original] of originals) {
+            target[method] = original;
+        }
+    }
+    return count;
+}
+
+test("inputs and parameters reach the processor and outputs come back", () => {
+    const processor = create();
+    const input = block(1);
+    // a single value is constant for the block
+    let output = run(processor, input, { Volume: new Float32Array([0.5]) });
+    output.forEach((channel, c) => assert.deepEqual(channel, input[c].map(sample => sample * 0.5)));
+
+    const volume = Float32Array.from({ length: BLOCK }, (_, i) => i / BLOCK);
+    output = run(processor, input, { Volume: volume });
+    output.forEach((channel, c) => assert.deepEqual(channel, input[c].map((sample, i) => sample * volume[i])));
+});
+
+test("missing channels are silent", () => {
+    const processor = create();
+    const input = block(1);
+    const output = run(processor, input.slice(0, 1), { Volume: new Float32Array([1]) });
+    assert.deepEqual(output[0], input[0]);
+    output.slice(1).forEach(channel => assert.ok(channel.every(sample => sample === 0)));
+});
+
+test("process() reuses its views and doesn't allocate typed arrays", () => {
+    const processor = create();
+    const input = block(1);
+    const parameters = { Volume: new Float32Array([0.5]) };
+    const output = outputs();
+    processor.process([input], output, parameters);
+    const views = [processor.inputViews, processor.outputViews, processor.parameterViews];
+
+    const RealFloat32Array = globalThis.Float32Array;
+    let constructed = 0;
+    globalThis.Float32Array = new Proxy(RealFloat32Array, {
+        construct(target, args) {
+            constructed++;
+            return Reflect.construct(target, args);
+        },
+    });
+    let calls;
+    try {
+        calls = counting(RealFloat32Array.prototype, ["subarray", "slice", "map"], () =>
+            counting(Array.prototype, ["map", "every", "forEach", "filter", "slice", "concat", "reduce", "some"], () => {
+                for (let i = 0; i < 100; i++) {
+                    processor.process([input], output, parameters);
+                }
+            })
+        );
+    } finally {
+        globalThis.Float32Array = RealFloat32Array;
+    }
+    assert.equal(constructed, 0);
+    assert.equal(calls, 0);
+    assert.deepEqual([processor.inputViews, processor.outputViews, processor.parameterViews], views);
+});
+
+test("views follow the memory when the processor grows it", () => {
+    const processor = create();
+    const input = block(1);
+    processor.processor.growOnProcess = true;
+    const output = run(processor, input, { Volume: new Float32Array([2]) });
+    output.forEach((channel, c) => assert.deepEqual(channel, input[c].map(sample => sample * 2)));
+    assert.equal(processor.float32Memory.buffer, processor.processor.memory.buffer);
+});
+
+test("port messages that aren't events or commands are ignored", () => {
+    const processor = create();
+    for (const data of [null, undefined, 5, "noteOn", { type: "noteOn" }, { type: "noteOn", data: null }]) {
+        processor.port.onmessage({ data });
+    }
+    assert.deepEqual(processor.processor.events, []);
+    processor.port.onmessage({ data: { type: "noteOn", data: { channel: 1, key: 60, velocity: 100 } } });
+    assert.deepEqual(processor.processor.events, [{ channel: 1, key: 60, velocity: 100, frame: 0 }]);
+});
+
+test("the notifications of a block are posted as one message", () => {
+    const processor = create();
+    const input = block(1);
+    run(processor, input, { Volume: new Float32Array([1]) });
+    assert.deepEqual(processor.posted, []);
+
+    processor.processor.notifications = [1, 3, 0, 0, 2, 7, 1.5, 0];
+    run(processor, input, { Volume: new Float32Array([1]) });
+    assert.equal(processor.posted.length, 1);
+    const [{ message, transfer }] = processor.posted;
+    assert.equal(message.type, "notifications");
+    assert.deepEqual(message.data, new Float64Array([1, 3, 0, 0, 2, 7, 1.5, 0]));
+    // a copy that can be transferred, not a view of the wasm memory
+    assert.deepEqual(transfer, [message.data.buffer]);
+    assert.notEqual(message.data.buffer, processor.memory.buffer);
+});
+
+test("the overflow count of the event queue is posted when it changes", () => {
+    const processor = create();
+    const input = block(1);
+    processor.processor.eventOverflowCount = 3;
+    run(processor, input, { Volume: new Float32Array([1]) });
+    run(processor, input, { Volume: new Float32Array([1]) });
+    assert.deepEqual(processor.posted.map(({ message }) => message), [{ type: "eventOverflow", data: { count: 3 } }]);
+});
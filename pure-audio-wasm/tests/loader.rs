@@ -0,0 +1,79 @@
+use pure_audio::{ParameterAutomationRate, ParameterDescriptor};
+use pure_audio_wasm::{validate_descriptors, LoaderError};
+
+fn descriptor(name: &'static str, default_value: f32) -> ParameterDescriptor {
+    ParameterDescriptor {
+        name,
+        default_value,
+        min_value: 0.0,
+        max_value: 1.0,
+        automation_rate: ParameterAutomationRate::K,
+    }
+}
+
+fn invalid(descriptors: &[ParameterDescriptor]) -> String {
+    match validate_descriptors(descriptors) {
+        Err(LoaderError::InvalidDescriptor(message)) => message,
+        result => panic!("expected an invalid descriptor, got {result:?}"),
+    }
+}
+
+#[test]
+fn valid_descriptors_pass() {
+    assert_eq!(validate_descriptors(&[]), Ok(()));
+    assert_eq!(validate_descriptors(&[descriptor("Volume", 1.0), descriptor("Cutoff frequency", 0.0)]), Ok(()));
+}
+
+#[test]
+fn names_must_be_embeddable() {
+    assert_eq!(invalid(&[descriptor("", 0.5)]), r#""" is not a valid parameter name"#);
+    for name in ["Don't", "\"Volume\"", "back\\slash", "two\nlines", "carriage\rreturn"] {
+        assert!(invalid(&[descriptor(name, 0.5)]).ends_with("is not a valid parameter name"));
+    }
+}
+
+#[test]
+fn names_must_be_unique() {
+    let descriptors = [descriptor("Volume", 0.5), descriptor("Pan", 0.5), descriptor("Volume", 1.0)];
+    assert_eq!(invalid(&descriptors), "Volume is declared twice");
+}
+
+#[test]
+fn names_must_have_an_accessor() {
+    let descriptors = [descriptor("Volume", 0.5), descriptor("volume", 0.5)];
+    assert_eq!(invalid(&descriptors), "volume has accessor volume, as does Volume");
+}
+
+#[test]
+fn values_must_be_finite_and_in_range() {
+    assert_eq!(invalid(&[descriptor("Volume", f32::NAN)]), "Volume has a value that is not finite");
+    let unbounded = ParameterDescriptor { max_value: f32::INFINITY, ..descriptor("Volume", 0.5) };
+    assert_eq!(invalid(&[unbounded]), "Volume has a value that is not finite");
+    assert_eq!(invalid(&[descriptor("Volume", 2.0)]), "Volume has default value 2 outside of 0..=1");
+    let inverted = ParameterDescriptor { min_value: 1.0, max_value: 0.0, ..descriptor("Volume", 0.5) };
+    assert_eq!(invalid(&[inverted]), "Volume has default value 0.5 outside of 1..=0");
+}
+
+#[test]
+fn errors_have_a_kind_and_a_message() {
+    let error = LoaderError::InvalidDescriptor("Volume is declared twice".into());
+    assert_eq!(error.kind(), "invalidDescriptor");
+    assert_eq!(error.message(), "Volume is declared twice");
+    assert_eq!(error.to_string(), "invalid parameter descriptor: Volume is declared twice");
+
+    let kinds = [
+        LoaderError::Fetch(String::new()),
+        LoaderError::HttpStatus(String::new()),
+        LoaderError::Compile(String::new()),
+        LoaderError::AddModule(String::new()),
+        LoaderError::CreateNode(String::new()),
+        LoaderError::InvalidDescriptor(String::new()),
+        LoaderError::InvalidSource(String::new()),
+    ]
+    .map(|error| error.kind());
+    assert_eq!(
+        kinds,
+        ["fetch", "httpStatus", "compile", "addModule", "createNode", "invalidDescriptor", "invalidSource"]
+    );
+    assert_eq!(LoaderError::HttpStatus("404 Not Found".into()).message(), "404 Not Found");
+}
@@ -0,0 +1,120 @@
+use pure_audio::{EventKind, InstrumentAudioData, Notification, OutputBuffer, DEFAULT_NOTIFICATION_QUEUE_CAPACITY};
+use pure_audio_wasm::{create_wasm_processor, WasmProcessor};
+
+// writes (offset, key) of every event of the block, in the order they are delivered
+fn record(
+    InstrumentAudioData {
+        events,
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: InstrumentAudioData,
+) {
+    for (event, slot) in events.iter().zip(output.chunks_exact_mut(2)) {
+        if let EventKind::NoteOn { key, .. } = event.kind {
+            slot.copy_from_slice(&[event.offset as f32, key as f32]);
+        }
+    }
+}
+
+// a custom notification with the key of every note
+fn notify_keys(InstrumentAudioData { events, mut messages, .. }: InstrumentAudioData) {
+    for event in events {
+        if let EventKind::NoteOn { key, .. } = event.kind {
+            messages.notify(Notification::Custom { id: key.into(), value: 0.5 });
+        }
+    }
+}
+
+// writes the channel of every event
+fn channels(
+    InstrumentAudioData {
+        events,
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: InstrumentAudioData,
+) {
+    for (event, sample) in events.iter().zip(output.iter_mut()) {
+        *sample = event.channel as f32;
+    }
+}
+
+fn process(processor: &mut WasmProcessor, current_frame: u64) -> Vec<(usize, u8)> {
+    processor.process(current_frame as f64);
+    // the output block the worklet copies from
+    let output = unsafe { std::slice::from_raw_parts(processor.get_outputs_ptr() as *const f32, 128) };
+    output
+        .chunks_exact(2)
+        .take_while(|slot| slot[1] != 0.0)
+        .map(|slot| (slot[0] as usize, slot[1] as u8))
+        .collect()
+}
+
+#[test]
+fn absolute_frames_become_offsets_within_the_block() {
+    let mut processor = create_wasm_processor(record, 48000.0);
+    processor.note_on(0, 1, 100, 1000.0);
+    processor.note_on(0, 2, 100, 1127.0);
+    processor.note_on(0, 3, 100, 1128.0);
+    assert_eq!(process(&mut processor, 1000), [(0, 1), (127, 2)]);
+    assert_eq!(process(&mut processor, 1128), [(0, 3)]);
+}
+
+#[test]
+fn late_events_are_delivered_at_offset_zero() {
+    let mut processor = create_wasm_processor(record, 48000.0);
+    processor.note_on(0, 1, 100, 100.0);
+    // as soon as possible
+    processor.note_on(0, 2, 100, 0.0);
+    // still in frame order
+    assert_eq!(process(&mut processor, 256), [(0, 2), (0, 1)]);
+}
+
+#[test]
+fn events_beyond_the_block_are_kept_for_later() {
+    let mut processor = create_wasm_processor(record, 48000.0);
+    processor.note_on(0, 1, 100, 300.0);
+    assert_eq!(process(&mut processor, 0), []);
+    assert_eq!(process(&mut processor, 128), []);
+    assert_eq!(process(&mut processor, 256), [(44, 1)]);
+    assert_eq!(process(&mut processor, 384), []);
+}
+
+#[test]
+fn events_on_the_same_frame_keep_their_arrival_order() {
+    let mut processor = create_wasm_processor(record, 48000.0);
+    processor.note_on(0, 3, 100, 10.0);
+    processor.note_on(0, 1, 100, 10.0);
+    processor.note_on(0, 5, 100, 5.0);
+    processor.note_on(0, 2, 100, 10.0);
+    assert_eq!(process(&mut processor, 0), [(5, 5), (10, 3), (10, 1), (10, 2)]);
+}
+
+#[test]
+fn channels_are_masked_to_four_bits() {
+    let mut processor = create_wasm_processor(channels, 48000.0);
+    processor.note_on(15, 60, 100, 0.0);
+    processor.note_on(17, 60, 100, 0.0);
+    processor.control_change(0xF3, 64, 127, 0.0);
+    processor.process(0.0);
+    let output = unsafe { std::slice::from_raw_parts(processor.get_outputs_ptr() as *const f32, 3) };
+    assert_eq!(output, [15.0, 1.0, 3.0]);
+}
+
+#[test]
+fn notifications_are_encoded_and_overflow_is_counted() {
+    let mut processor = create_wasm_processor(notify_keys, 48000.0);
+    for key in 0..DEFAULT_NOTIFICATION_QUEUE_CAPACITY + 2 {
+        processor.note_on(0, key as u8, 100, 0.0);
+    }
+    processor.process(0.0);
+
+    assert_eq!(processor.encode_notifications(), DEFAULT_NOTIFICATION_QUEUE_CAPACITY);
+    assert_eq!(processor.notification_drop_count(), 2.0);
+    // type, id, value and padding, see message.rs
+    let encoded = unsafe { std::slice::from_raw_parts(processor.get_notifications_ptr() as *const f64, 8) };
+    assert_eq!(encoded, [2.0, 0.0, 0.5, 0.0, 2.0, 1.0, 0.5, 0.0]);
+
+    // the queue was emptied
+    processor.process(128.0);
+    assert_eq!(processor.encode_notifications(), 0);
+}
@@ -0,0 +1,76 @@
+use pure_audio::{EffectAudioData, InstrumentAudioData, ParameterAutomationRate, ParameterDescriptor};
+use pure_audio_wasm::{__private::node_interface, parameter_accessors, typescript_definitions, LoaderError};
+
+fn descriptor(name: &'static str) -> ParameterDescriptor {
+    ParameterDescriptor {
+        name,
+        default_value: 1.0,
+        min_value: 0.0,
+        max_value: 1.0,
+        automation_rate: ParameterAutomationRate::A,
+    }
+}
+
+fn accessors(names: &[&'static str]) -> Result<Vec<(String, &'static str)>, String> {
+    let descriptors: Vec<_> = names.iter().map(|name| descriptor(name)).collect();
+    match parameter_accessors(&descriptors) {
+        Ok(accessors) => Ok(accessors.into_iter().map(|(accessor, descriptor)| (accessor, descriptor.name)).collect()),
+        Err(LoaderError::InvalidDescriptor(message)) => Err(message),
+        Err(error) => panic!("expected an invalid descriptor, got {error:?}"),
+    }
+}
+
+#[test]
+fn accessors_are_lower_camel_case() {
+    assert_eq!(
+        accessors(&["Volume", "Cutoff frequency", "LFO rate", "mix-level"]),
+        Ok(vec![
+            ("volume".to_string(), "Volume"),
+            ("cutoffFrequency".to_string(), "Cutoff frequency"),
+            ("lfoRate".to_string(), "LFO rate"),
+            ("mixLevel".to_string(), "mix-level"),
+        ])
+    );
+}
+
+#[test]
+fn accessors_never_shadow_the_node() {
+    assert_eq!(accessors(&["Gain", "Connect"]), Err("Connect has accessor connect, a member of the node".into()));
+    assert_eq!(accessors(&["Note on"]), Err("Note on has accessor noteOn, a member of the node".into()));
+    assert_eq!(accessors(&["Gain", "gain"]), Err("gain has accessor gain, as does Gain".into()));
+    let not_an_identifier = r#"2nd voice has accessor "2ndVoice", which is not an identifier"#;
+    assert_eq!(accessors(&["2nd voice"]), Err(not_an_identifier.into()));
+    assert_eq!(accessors(&["--"]), Err(r#"-- has accessor "", which is not an identifier"#.into()));
+}
+
+#[test]
+fn definitions_augment_the_node_types() {
+    let gain = [descriptor("Volume")];
+    let definitions = typescript_definitions("./gain_loader", &[("Gain", &gain), ("Oscillator", &[])]).unwrap();
+    assert!(definitions.contains("declare module \"./gain_loader\" {"));
+    assert!(definitions.contains(
+        "    interface GainAudioWorkletNode {\n        \
+        /** `Volume`, 0 to 1, 1 by default, a-rate */\n        \
+        volume(): AudioParam;\n    \
+        }\n"
+    ));
+    assert!(definitions.contains("    interface OscillatorAudioWorkletNode {\n    }\n"));
+}
+
+fn silent_effect(_: EffectAudioData<1, 1, 1, 128>) {}
+
+fn silent_instrument(_: InstrumentAudioData<1, 1, 128>) {}
+
+#[test]
+fn node_types_extend_the_node_type_of_the_processor() {
+    const EFFECT: &str = node_interface("EffectAudioWorkletNode", &|| silent_effect).as_str();
+    const INSTRUMENT: &str = node_interface("InstrumentNode", &|| silent_instrument).as_str();
+    assert_eq!(EFFECT, "export interface EffectAudioWorkletNode extends AudioWorkletNode {}");
+    assert_eq!(INSTRUMENT, "export interface InstrumentNode extends InstrumentAudioWorkletNode {}");
+}
+
+#[test]
+fn definitions_need_valid_accessors() {
+    let definitions = typescript_definitions("./gain_loader", &[("Gain", &[descriptor("Port")])]);
+    assert_eq!(definitions, Err(LoaderError::InvalidDescriptor("Port has accessor port, a member of the node".into())));
+}
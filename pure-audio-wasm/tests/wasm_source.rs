@@ -0,0 +1,91 @@
+//! Runs in a browser, with wasm-bindgen-test-runner as the runner of the wasm32-unknown-unknown target:
+//! `cargo test -p pure-audio-wasm --target wasm32-unknown-unknown --test wasm_source`
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Float32Array, Map, Object, Reflect, Uint8Array, WebAssembly};
+use pure_audio_wasm::WasmSource;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+use web_sys::OfflineAudioContext;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+// the smallest valid module, magic and version
+const EMPTY_MODULE: [u8; 8] = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+const EMPTY_MODULE_URL: &str = "data:application/wasm;base64,AGFzbQEAAAA=";
+const INVALID_MODULE_URL: &str = "data:application/wasm;base64,AAAAAA==";
+
+fn bytes(source: WasmSource) -> Vec<u8> {
+    match source {
+        WasmSource::Bytes(bytes) => bytes,
+        other => panic!("expected bytes, got {other:?}"),
+    }
+}
+
+fn context() -> OfflineAudioContext {
+    OfflineAudioContext::new_with_number_of_channels_and_length_and_sample_rate(1, 128, 48000.0).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn accepts_urls_modules_and_buffers() {
+    let source = WasmSource::try_from(JsValue::from_str("gain_bg.wasm"));
+    assert!(matches!(source, Ok(WasmSource::Url(url)) if url == "gain_bg.wasm"));
+
+    let module = WebAssembly::Module::new(&Uint8Array::from(&EMPTY_MODULE[..])).unwrap();
+    assert!(matches!(WasmSource::try_from(JsValue::from(module)), Ok(WasmSource::Module(_))));
+
+    let buffer = Uint8Array::from(&EMPTY_MODULE[..]).buffer();
+    assert_eq!(bytes(WasmSource::try_from(JsValue::from(buffer.clone())).unwrap()), EMPTY_MODULE);
+
+    // only the viewed bytes, whatever the element type
+    let view = Float32Array::new_with_byte_offset_and_length(&buffer, 4, 1);
+    assert_eq!(bytes(WasmSource::try_from(JsValue::from(view)).unwrap()), EMPTY_MODULE[4..]);
+}
+
+#[wasm_bindgen_test]
+fn rejects_other_values() {
+    for value in [JsValue::UNDEFINED, JsValue::NULL, JsValue::from(5), Object::new().into()] {
+        assert_eq!(WasmSource::try_from(value).unwrap_err().kind(), "invalidSource");
+    }
+}
+
+#[wasm_bindgen_test]
+fn glue_is_given_or_next_to_the_wasm_bindgen_output() {
+    let url = WasmSource::from("pkg/gain_bg.wasm");
+    assert!(url.glue_url(None).unwrap().ends_with("/pkg/gain.js"));
+    assert!(url.glue_url(Some("loader/glue.js")).unwrap().ends_with("/loader/glue.js"));
+
+    // no guessing for other names, modules and bytes
+    let bytes = WasmSource::from(&EMPTY_MODULE[..]);
+    assert!(bytes.glue_url(Some("gain.js")).unwrap().ends_with("/gain.js"));
+    for source in [WasmSource::from("processors.wasm"), bytes] {
+        assert_eq!(source.glue_url(None).unwrap_err().kind(), "invalidSource");
+    }
+}
+
+#[wasm_bindgen_test]
+async fn compiles_urls_and_bytes_once_per_context() {
+    let ctx = context();
+    for source in [WasmSource::from(EMPTY_MODULE_URL), WasmSource::from(&EMPTY_MODULE[..])] {
+        let module = source.compile(&ctx).await.unwrap();
+        assert!(Object::is(&module, &source.compile(&ctx).await.unwrap()));
+        assert!(!Object::is(&module, &source.compile(&context()).await.unwrap()));
+    }
+
+    // the same bytes from another buffer
+    let buffer = Uint8Array::from(&EMPTY_MODULE[..]).buffer();
+    let module = WasmSource::from(&EMPTY_MODULE[..]).compile(&ctx).await.unwrap();
+    let source = WasmSource::try_from(JsValue::from(buffer)).unwrap();
+    assert!(Object::is(&module, &source.compile(&ctx).await.unwrap()));
+}
+
+#[wasm_bindgen_test]
+async fn failed_compilations_are_not_cached() {
+    let ctx = context();
+    for source in [WasmSource::from(INVALID_MODULE_URL), WasmSource::from(&[0u8; 4][..])] {
+        assert_eq!(source.compile(&ctx).await.unwrap_err().kind(), "compile");
+    }
+
+    let compiled_modules: Map = Reflect::get(&ctx, &"compiledModules".into()).unwrap().unchecked_into();
+    assert_eq!(compiled_modules.size(), 0);
+}
@@ -0,0 +1,55 @@
+use pure_audio::{AudioRate, EffectAudioData, ParameterAutomationRate, ParameterDescriptor, ProcessorParameter};
+use pure_audio_wasm::worklet_module;
+use std::{fs, io::ErrorKind, path::Path, process::Command};
+
+#[derive(Copy, Clone)]
+struct Volume;
+
+impl ProcessorParameter for Volume {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::A,
+        default_value: 1.0,
+        max_value: 1.0,
+        min_value: 0.0,
+        name: "Volume",
+    };
+
+    fn from_parameter(_: f32) -> Self {
+        Volume
+    }
+}
+
+// only the shape matters, the harness runs a fake processor
+fn stereo_gain(_: EffectAudioData<1, 1, 2>, _: AudioRate<Volume>) {}
+
+fn file_url(path: &Path) -> String {
+    let path = path.display().to_string().replace('\\', "/");
+    match path.starts_with('/') {
+        true => format!("file://{path}"),
+        false => format!("file:///{path}"),
+    }
+}
+
+// the generated glue must stay allocation free and keep copying correctly, see tests/js/worklet/harness.mjs
+#[test]
+fn generated_worklet_runs_in_node() {
+    let js = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/js/worklet");
+    let code = worklet_module("StereoGain", &file_url(&js.join("fakeGlue.mjs")), &stereo_gain).unwrap();
+    let module = Path::new(env!("CARGO_TARGET_TMPDIR")).join("stereoGain.mjs");
+    fs::write(&module, code).unwrap();
+
+    let output = match Command::new("node").arg(js.join("harness.mjs")).arg(file_url(&module)).arg("2").output() {
+        Ok(output) => output,
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            eprintln!("skipped, node is not installed");
+            return;
+        }
+        Err(error) => panic!("{error}"),
+    };
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,142 @@
+use crate::{Command, Event, Messages, Processor};
+use std::marker::PhantomData;
+
+/// Runs a processor compiled for `BLOCK_SIZE` frames under a host that delivers any number of frames per call.
+///
+/// Input, parameters and events are buffered until a whole block is available, so the output is delayed by
+/// [`latency`](BlockAdapter::latency) frames.
+///
+/// ```ignore
+/// let mut adapter = BlockAdapter::new(gain::process.into_processor(sample_rate));
+/// adapter.process(&[[&input]], &mut [[&mut output]], &[&volume], &[], &mut messages);
+/// ```
+pub struct BlockAdapter<
+    P,
+    const IS_INSTRUMENT: bool,
+    const NUM_INPUTS: usize,
+    const NUM_OUTPUTS: usize,
+    const NUM_CHANNELS: usize,
+    const BLOCK_SIZE: usize,
+    const NUM_PARAMS: usize,
+    Params,
+> {
+    processor: P,
+    inputs: [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
+    outputs: [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+    parameters: [[f32; BLOCK_SIZE]; NUM_PARAMS],
+    // offsets are relative to the start of the block being filled
+    pending_events: Vec<Event>,
+    block_events: Vec<Event>,
+    // delivered with the next block
+    pending_commands: Vec<Command>,
+    // frames filled in the current block
+    position: usize,
+    marker: PhantomData<Params>,
+}
+
+impl<
+        P,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const BLOCK_SIZE: usize,
+        const NUM_PARAMS: usize,
+        Params,
+    > BlockAdapter<P, IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, Params>
+where
+    P: Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, Params>,
+{
+    pub fn new(processor: P) -> Self {
+        Self {
+            processor,
+            inputs: [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
+            outputs: [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+            parameters: [[0.0; BLOCK_SIZE]; NUM_PARAMS],
+            pending_events: Vec::with_capacity(BLOCK_SIZE),
+            block_events: Vec::with_capacity(BLOCK_SIZE),
+            pending_commands: Vec::with_capacity(BLOCK_SIZE),
+            position: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// The delay in frames between input (and events) and the corresponding output.
+    pub fn latency(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    /// Processes as many frames as the output slices hold, or the input slices when there are no outputs.
+    ///
+    /// Parameter slices hold either a single value for the whole call or one value per frame, as in Web Audio. Event
+    /// offsets are frames relative to the start of this call. Commands are delivered with the next block, notifications
+    /// are queued in `messages` as soon as the block is processed.
+    pub fn process(
+        &mut self,
+        inputs: &[[&[f32]; NUM_CHANNELS]; NUM_INPUTS],
+        outputs: &mut [[&mut [f32]; NUM_CHANNELS]; NUM_OUTPUTS],
+        parameters: &[&[f32]; NUM_PARAMS],
+        events: &[Event],
+        messages: &mut Messages,
+    ) {
+        let num_frames = outputs
+            .iter()
+            .flatten()
+            .map(|channel| channel.len())
+            .chain(inputs.iter().flatten().map(|channel| channel.len()))
+            .next()
+            .unwrap_or(0);
+
+        self.pending_events
+            .extend(events.iter().map(|event| Event { offset: self.position + event.offset, ..*event }));
+        self.pending_commands.extend_from_slice(messages.commands);
+
+        let mut start = 0;
+        while start < num_frames {
+            let length = (BLOCK_SIZE - self.position).min(num_frames - start);
+            let frames = start..start + length;
+            let block_frames = self.position..self.position + length;
+
+            for (block, input) in self.inputs.iter_mut().zip(inputs) {
+                for (block_channel, channel) in block.iter_mut().zip(input) {
+                    block_channel[block_frames.clone()].copy_from_slice(&channel[frames.clone()]);
+                }
+            }
+            for (block, values) in self.parameters.iter_mut().zip(parameters) {
+                match values.len() {
+                    1 => block[block_frames.clone()].fill(values[0]),
+                    _ => block[block_frames.clone()].copy_from_slice(&values[frames.clone()]),
+                }
+            }
+            // the output of the previous block
+            for (block, output) in self.outputs.iter().zip(outputs.iter_mut()) {
+                for (block_channel, channel) in block.iter().zip(output.iter_mut()) {
+                    channel[frames.clone()].copy_from_slice(&block_channel[block_frames.clone()]);
+                }
+            }
+
+            self.position += length;
+            start += length;
+            if self.position == BLOCK_SIZE {
+                self.process_block(messages);
+                self.position = 0;
+            }
+        }
+    }
+
+    fn process_block(&mut self, messages: &mut Messages) {
+        // stable sort keeps the given order of events on the same frame
+        self.pending_events.sort_by_key(|event| event.offset);
+        let count = self.pending_events.partition_point(|event| event.offset < BLOCK_SIZE);
+        self.block_events.clear();
+        self.block_events.extend(self.pending_events.drain(..count));
+        for event in &mut self.pending_events {
+            event.offset -= BLOCK_SIZE;
+        }
+
+        self.outputs = [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS];
+        let mut block_messages = Messages::new(&self.pending_commands, messages.notifications);
+        self.processor.process(&self.inputs, &mut self.outputs, &self.parameters, &self.block_events, &mut block_messages);
+        self.pending_commands.clear();
+    }
+}
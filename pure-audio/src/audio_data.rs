@@ -1,6 +1,7 @@
-use crate::{buffer::{InputBuffer, OutputBuffer}, event::Event};
+use crate::{buffer::{InputBuffer, InputSlices, OutputBuffer, OutputSlices}, event::Event, message::Messages};
+use std::marker::PhantomData;
 
-const DEFAULT_BLOCK_SIZE: usize = 128;
+pub(crate) const DEFAULT_BLOCK_SIZE: usize = 128;
 
 pub struct EffectAudioData<
     'a,
@@ -14,6 +15,7 @@ pub struct EffectAudioData<
     pub outputs: OutputBuffer<'a, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE>,
     pub sample_rate: f32,
     pub state: &'a mut S,
+    pub messages: Messages<'a>,
 }
 
 pub struct InstrumentAudioData<
@@ -27,4 +29,43 @@ pub struct InstrumentAudioData<
     pub outputs: OutputBuffer<'a, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE>,
     pub sample_rate: f32,
     pub state: &'a mut S,
+    pub messages: Messages<'a>,
+}
+
+/// Audio data of a sub-block, see [`SampleAccurate`](crate::SampleAccurate).
+pub struct EffectSliceData<
+    'a,
+    const NUM_INPUTS: usize = 1,
+    const NUM_OUTPUTS: usize = 1,
+    const NUM_CHANNELS: usize = 1,
+    const BLOCK_SIZE: usize = DEFAULT_BLOCK_SIZE,
+    S = (),
+> {
+    pub inputs: InputSlices<'a, NUM_INPUTS, NUM_CHANNELS>,
+    pub outputs: OutputSlices<'a, NUM_OUTPUTS, NUM_CHANNELS>,
+    pub sample_rate: f32,
+    pub state: &'a mut S,
+    /// Commands are delivered with the first slice of the block.
+    pub messages: Messages<'a>,
+    // slices are at most BLOCK_SIZE frames
+    pub(crate) marker: PhantomData<[(); BLOCK_SIZE]>,
+}
+
+/// Audio data of a sub-block, see [`SampleAccurate`](crate::SampleAccurate). All events happen on the first frame of
+/// the slice and have offset 0.
+pub struct InstrumentSliceData<
+    'a,
+    const NUM_OUTPUTS: usize = 1,
+    const NUM_CHANNELS: usize = 1,
+    const BLOCK_SIZE: usize = DEFAULT_BLOCK_SIZE,
+    S = (),
+> {
+    pub events: &'a [Event],
+    pub outputs: OutputSlices<'a, NUM_OUTPUTS, NUM_CHANNELS>,
+    pub sample_rate: f32,
+    pub state: &'a mut S,
+    /// Commands are delivered with the first slice of the block.
+    pub messages: Messages<'a>,
+    // slices are at most BLOCK_SIZE frames
+    pub(crate) marker: PhantomData<[(); BLOCK_SIZE]>,
 }
@@ -20,7 +20,7 @@ impl<'a, const SIZE: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize>
     type Target = Buffer<SIZE, NUM_CHANNELS, BLOCK_SIZE>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        self.0
     }
 }
 
@@ -41,13 +41,55 @@ impl<'a, const SIZE: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize>
     type Target = Buffer<SIZE, NUM_CHANNELS, BLOCK_SIZE>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        self.0
     }
 }
 
 impl<'a, const SIZE: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize> DerefMut
     for OutputBuffer<'a, SIZE, NUM_CHANNELS, BLOCK_SIZE>
 {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+/// Input slices of equal length, up to a block.
+pub struct InputSlices<'a, const SIZE: usize, const NUM_CHANNELS: usize>(pub [[&'a [f32]; NUM_CHANNELS]; SIZE]);
+
+impl<'a, const SIZE: usize, const NUM_CHANNELS: usize> InputSlices<'a, SIZE, NUM_CHANNELS> {
+    #[inline]
+    pub fn new(data: [[&'a [f32]; NUM_CHANNELS]; SIZE]) -> Self {
+        Self(data)
+    }
+}
+
+impl<'a, const SIZE: usize, const NUM_CHANNELS: usize> Deref for InputSlices<'a, SIZE, NUM_CHANNELS> {
+    type Target = [[&'a [f32]; NUM_CHANNELS]; SIZE];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Output slices of equal length, up to a block.
+pub struct OutputSlices<'a, const SIZE: usize, const NUM_CHANNELS: usize>(pub [[&'a mut [f32]; NUM_CHANNELS]; SIZE]);
+
+impl<'a, const SIZE: usize, const NUM_CHANNELS: usize> OutputSlices<'a, SIZE, NUM_CHANNELS> {
+    #[inline]
+    pub fn new(data: [[&'a mut [f32]; NUM_CHANNELS]; SIZE]) -> Self {
+        Self(data)
+    }
+}
+
+impl<'a, const SIZE: usize, const NUM_CHANNELS: usize> Deref for OutputSlices<'a, SIZE, NUM_CHANNELS> {
+    type Target = [[&'a mut [f32]; NUM_CHANNELS]; SIZE];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, const SIZE: usize, const NUM_CHANNELS: usize> DerefMut for OutputSlices<'a, SIZE, NUM_CHANNELS> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
@@ -1,4 +1,55 @@
-pub enum Event {
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Event {
+    /// frame offset within the current block
+    pub offset: usize,
+    /// MIDI channel (0-15)
+    pub channel: u8,
+    pub kind: EventKind
+}
+
+impl Event {
+    #[inline]
+    pub fn new(offset: usize, channel: u8, kind: EventKind) -> Self {
+        Self { offset, channel, kind }
+    }
+}
+
+/// An event at an absolute frame, e.g. of an offline rendering or a MIDI file, rather than within a block.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TimedEvent {
+    /// frame from the start
+    pub frame: u64,
+    /// MIDI channel (0-15)
+    pub channel: u8,
+    pub kind: EventKind
+}
+
+impl TimedEvent {
+    #[inline]
+    pub fn new(frame: u64, channel: u8, kind: EventKind) -> Self {
+        Self { frame, channel, kind }
+    }
+
+    /// The event at `offset` within the block it falls in.
+    #[inline]
+    pub fn at_offset(&self, offset: usize) -> Event {
+        Event::new(offset, self.channel, self.kind)
+    }
+}
+
+/// MIDI 1.0 channel voice messages, data bytes are 0-127 unless stated otherwise
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EventKind {
     NoteOn { key: u8, velocity: u8 },
-    NoteOff { key: u8, velocity: u8 }
-}
\ No newline at end of file
+    NoteOff { key: u8, velocity: u8 },
+    PolyphonicKeyPressure { key: u8, pressure: u8 },
+    ControlChange { controller: u8, value: u8 },
+    ProgramChange { program: u8 },
+    ChannelPressure { pressure: u8 },
+    /// 14-bit value (0-16383), 8192 is the center
+    PitchBend { value: u16 }
+}
+
+impl EventKind {
+    pub const PITCH_BEND_CENTER: u16 = 8192;
+}
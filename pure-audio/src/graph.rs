@@ -0,0 +1,323 @@
+//! Composes processors inside a single processor.
+//!
+//! ```ignore
+//! // gain -> filter, the graph has the parameters of gain followed by those of filter, whose names are numbered when
+//! // gain already has them, e.g. Volume and Volume 2
+//! let chain = Graph::<3>::series(gain::process, filter::process);
+//! ```
+//!
+//! Routing between a different number of inputs and outputs is done by plain processors such as [`mix`] and
+//! [`split`], or any other pure function. Both processors of a graph receive all events and commands.
+
+use crate::{
+    EffectAudioData, Event, InputBuffer, IntoProcessor, Messages, OutputBuffer, ParameterDescriptor, Processor,
+};
+use std::{collections::BTreeSet, sync::Mutex};
+
+/// Builds graph nodes with `NUM_PARAMS` parameters in total, which must equal the sum of the parameters of the
+/// composed processors.
+pub struct Graph<const NUM_PARAMS: usize>;
+
+impl<const NUM_PARAMS: usize> Graph<NUM_PARAMS> {
+    /// Feeds the outputs of `a` into the inputs of `b`.
+    pub fn series<
+        A,
+        B,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_CONNECTIONS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const BLOCK_SIZE: usize,
+        const NUM_PARAMS_A: usize,
+        const NUM_PARAMS_B: usize,
+        ParamsA,
+        ParamsB,
+        SA,
+        SB,
+    >(
+        a: A,
+        b: B,
+    ) -> Series<A, B, NUM_PARAMS, NUM_CONNECTIONS, NUM_PARAMS_A, NUM_PARAMS_B>
+    where
+        A: IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_CONNECTIONS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, ParamsA, SA>,
+        B: IntoProcessor<false, NUM_CONNECTIONS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_B, ParamsB, SB>,
+    {
+        const { assert!(NUM_PARAMS == NUM_PARAMS_A + NUM_PARAMS_B, "NUM_PARAMS must be the sum of the parameters of both processors") };
+        Series { a, b }
+    }
+
+    /// Feeds the same inputs and events to `a` and `b` and sums their outputs.
+    pub fn parallel<
+        A,
+        B,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const BLOCK_SIZE: usize,
+        const NUM_PARAMS_A: usize,
+        const NUM_PARAMS_B: usize,
+        ParamsA,
+        ParamsB,
+        SA,
+        SB,
+    >(
+        a: A,
+        b: B,
+    ) -> Parallel<A, B, NUM_PARAMS, NUM_PARAMS_A, NUM_PARAMS_B>
+    where
+        A: IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, ParamsA, SA>,
+        B: IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_B, ParamsB, SB>,
+    {
+        const { assert!(NUM_PARAMS == NUM_PARAMS_A + NUM_PARAMS_B, "NUM_PARAMS must be the sum of the parameters of both processors") };
+        Parallel { a, b }
+    }
+}
+
+pub struct Series<A, B, const NUM_PARAMS: usize, const NUM_CONNECTIONS: usize, const NUM_PARAMS_A: usize, const NUM_PARAMS_B: usize> {
+    a: A,
+    b: B,
+}
+
+pub struct Parallel<A, B, const NUM_PARAMS: usize, const NUM_PARAMS_A: usize, const NUM_PARAMS_B: usize> {
+    a: A,
+    b: B,
+}
+
+/// Sums all inputs into a single output.
+pub fn mix<const NUM_INPUTS: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize>(
+    EffectAudioData {
+        inputs: InputBuffer(inputs),
+        outputs: OutputBuffer([output]),
+        ..
+    }: EffectAudioData<NUM_INPUTS, 1, NUM_CHANNELS, BLOCK_SIZE>,
+) {
+    for input in inputs {
+        for (output_channel, input_channel) in output.iter_mut().zip(input) {
+            for (output_sample, input_sample) in output_channel.iter_mut().zip(input_channel) {
+                *output_sample += input_sample;
+            }
+        }
+    }
+}
+
+/// Copies a single input to all outputs.
+pub fn split<const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize>(
+    EffectAudioData {
+        inputs: InputBuffer([input]),
+        outputs: OutputBuffer(outputs),
+        ..
+    }: EffectAudioData<1, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE>,
+) {
+    outputs.fill(*input);
+}
+
+struct SeriesProcessor<PA, PB, const NUM_CONNECTIONS: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize, const NUM_PARAMS_A: usize, const NUM_PARAMS_B: usize> {
+    a: PA,
+    b: PB,
+    connections: [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_CONNECTIONS],
+}
+
+struct ParallelProcessor<PA, PB, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize, const NUM_PARAMS_A: usize, const NUM_PARAMS_B: usize> {
+    a: PA,
+    b: PB,
+    outputs_b: [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+}
+
+#[inline]
+fn split_parameters<const BLOCK_SIZE: usize, const NUM_PARAMS: usize, const NUM_PARAMS_A: usize, const NUM_PARAMS_B: usize>(
+    parameters: &[[f32; BLOCK_SIZE]; NUM_PARAMS],
+) -> (&[[f32; BLOCK_SIZE]; NUM_PARAMS_A], &[[f32; BLOCK_SIZE]; NUM_PARAMS_B]) {
+    let (a, b) = parameters.split_at(NUM_PARAMS_A);
+    (a.try_into().unwrap(), b.try_into().unwrap())
+}
+
+// names of b that are taken by then are numbered, e.g. series(scale, scale) has Scale and Scale 2
+fn concat_descriptors<const NUM_PARAMS: usize, const NUM_PARAMS_A: usize, const NUM_PARAMS_B: usize>(
+    a: [ParameterDescriptor; NUM_PARAMS_A],
+    b: [ParameterDescriptor; NUM_PARAMS_B],
+) -> [ParameterDescriptor; NUM_PARAMS] {
+    let mut descriptors: [ParameterDescriptor; NUM_PARAMS] =
+        std::array::from_fn(|i| if i < NUM_PARAMS_A { a[i] } else { b[i - NUM_PARAMS_A] });
+    for i in NUM_PARAMS_A..NUM_PARAMS {
+        let name = descriptors[i].name;
+        if descriptors[..i].iter().any(|descriptor| descriptor.name == name) {
+            // nor taken by a later name of b
+            let taken = |name: &str| descriptors.iter().any(|descriptor| descriptor.name == name);
+            let number = (2..).find(|number| !taken(&format!("{name} {number}"))).unwrap();
+            descriptors[i].name = intern(format!("{name} {number}"));
+        }
+    }
+    descriptors
+}
+
+// descriptors name their parameter with a &'static str, each numbered name is leaked once
+fn intern(name: String) -> &'static str {
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut names = NAMES.lock().unwrap();
+    match names.get(name.as_str()) {
+        Some(name) => name,
+        None => {
+            let name = Box::leak(name.into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+impl<
+        A,
+        B,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_CONNECTIONS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const BLOCK_SIZE: usize,
+        const NUM_PARAMS: usize,
+        const NUM_PARAMS_A: usize,
+        const NUM_PARAMS_B: usize,
+        ParamsA,
+        ParamsB,
+        SA,
+        SB,
+    > IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, (ParamsA, ParamsB), (SA, SB)>
+    for Series<A, B, NUM_PARAMS, NUM_CONNECTIONS, NUM_PARAMS_A, NUM_PARAMS_B>
+where
+    A: IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_CONNECTIONS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, ParamsA, SA>,
+    B: IntoProcessor<false, NUM_CONNECTIONS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_B, ParamsB, SB>,
+{
+    fn get_parameter_descriptors() -> [ParameterDescriptor; NUM_PARAMS] {
+        concat_descriptors(A::get_parameter_descriptors(), B::get_parameter_descriptors())
+    }
+
+    fn into_processor(
+        self,
+        sample_rate: f32,
+    ) -> impl Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, (ParamsA, ParamsB)> {
+        SeriesProcessor::<_, _, NUM_CONNECTIONS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, NUM_PARAMS_B> {
+            a: self.a.into_processor(sample_rate),
+            b: self.b.into_processor(sample_rate),
+            connections: [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_CONNECTIONS],
+        }
+    }
+}
+
+impl<
+        PA,
+        PB,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_CONNECTIONS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const BLOCK_SIZE: usize,
+        const NUM_PARAMS: usize,
+        const NUM_PARAMS_A: usize,
+        const NUM_PARAMS_B: usize,
+        ParamsA,
+        ParamsB,
+    > Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, (ParamsA, ParamsB)>
+    for SeriesProcessor<PA, PB, NUM_CONNECTIONS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, NUM_PARAMS_B>
+where
+    PA: Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_CONNECTIONS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, ParamsA>,
+    PB: Processor<false, NUM_CONNECTIONS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_B, ParamsB>,
+{
+    #[inline]
+    fn process(
+        &mut self,
+        inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
+        outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+        parameters: &[[f32; BLOCK_SIZE]; NUM_PARAMS],
+        events: &[Event],
+        messages: &mut Messages
+    ) {
+        let (parameters_a, parameters_b) = split_parameters::<BLOCK_SIZE, NUM_PARAMS, NUM_PARAMS_A, NUM_PARAMS_B>(parameters);
+        // clear connections
+        self.connections = [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_CONNECTIONS];
+        self.a.process(inputs, &mut self.connections, parameters_a, events, messages);
+        self.b.process(&self.connections, outputs, parameters_b, events, messages);
+    }
+}
+
+impl<
+        A,
+        B,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const BLOCK_SIZE: usize,
+        const NUM_PARAMS: usize,
+        const NUM_PARAMS_A: usize,
+        const NUM_PARAMS_B: usize,
+        ParamsA,
+        ParamsB,
+        SA,
+        SB,
+    > IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, (ParamsA, ParamsB), (SA, SB)>
+    for Parallel<A, B, NUM_PARAMS, NUM_PARAMS_A, NUM_PARAMS_B>
+where
+    A: IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, ParamsA, SA>,
+    B: IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_B, ParamsB, SB>,
+{
+    fn get_parameter_descriptors() -> [ParameterDescriptor; NUM_PARAMS] {
+        concat_descriptors(A::get_parameter_descriptors(), B::get_parameter_descriptors())
+    }
+
+    fn into_processor(
+        self,
+        sample_rate: f32,
+    ) -> impl Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, (ParamsA, ParamsB)> {
+        ParallelProcessor::<_, _, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, NUM_PARAMS_B> {
+            a: self.a.into_processor(sample_rate),
+            b: self.b.into_processor(sample_rate),
+            outputs_b: [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+        }
+    }
+}
+
+impl<
+        PA,
+        PB,
+        const IS_INSTRUMENT: bool,
+        const NUM_INPUTS: usize,
+        const NUM_OUTPUTS: usize,
+        const NUM_CHANNELS: usize,
+        const BLOCK_SIZE: usize,
+        const NUM_PARAMS: usize,
+        const NUM_PARAMS_A: usize,
+        const NUM_PARAMS_B: usize,
+        ParamsA,
+        ParamsB,
+    > Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, (ParamsA, ParamsB)>
+    for ParallelProcessor<PA, PB, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, NUM_PARAMS_B>
+where
+    PA: Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_A, ParamsA>,
+    PB: Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS_B, ParamsB>,
+{
+    #[inline]
+    fn process(
+        &mut self,
+        inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
+        outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+        parameters: &[[f32; BLOCK_SIZE]; NUM_PARAMS],
+        events: &[Event],
+        messages: &mut Messages
+    ) {
+        let (parameters_a, parameters_b) = split_parameters::<BLOCK_SIZE, NUM_PARAMS, NUM_PARAMS_A, NUM_PARAMS_B>(parameters);
+        // clear outputs
+        self.outputs_b = [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS];
+        self.a.process(inputs, outputs, parameters_a, events, messages);
+        self.b.process(inputs, &mut self.outputs_b, parameters_b, events, messages);
+
+        for (output, output_b) in outputs.iter_mut().zip(&self.outputs_b) {
+            for (channel, channel_b) in output.iter_mut().zip(output_b) {
+                for (sample, sample_b) in channel.iter_mut().zip(channel_b) {
+                    *sample += sample_b;
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,22 @@
+mod adapter;
 mod audio_data;
 mod buffer;
 mod event;
+pub mod graph;
+mod message;
+pub mod midi;
 mod parameter;
 mod processor;
+mod render;
+mod sample_accurate;
 
 // re-export
+pub use adapter::*;
 pub use audio_data::*;
 pub use buffer::*;
 pub use event::*;
+pub use message::*;
 pub use parameter::*;
-pub use processor::*;
\ No newline at end of file
+pub use processor::*;
+pub use render::*;
+pub use sample_accurate::*;
\ No newline at end of file
@@ -0,0 +1,185 @@
+/// A message from the node to its processor, delivered with the next block.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Return to the initial state, e.g. silence all voices.
+    Reset,
+    /// Application defined, `id` tells commands apart.
+    Custom { id: u32, value: f64 },
+}
+
+/// A message from a processor to its node, sent after the block.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Notification {
+    /// Peak level of an output channel.
+    Meter { output: usize, channel: usize, peak: f32 },
+    /// The number of sounding voices changed.
+    VoiceCount(usize),
+    /// Application defined, `id` tells notifications apart.
+    Custom { id: u32, value: f64 },
+}
+
+/// An application defined message, carried by [`Command::Custom`] or [`Notification::Custom`].
+///
+/// Commands and notifications are a fixed set rather than types declared by each processor, so that they are copied
+/// without allocating on the audio thread and encoded the same way for every processor. Application types are
+/// converted to and from the custom variants instead:
+///
+/// ```ignore
+/// enum Control { Mute, Transpose(i8) }
+///
+/// impl CustomMessage for Control { ... }
+///
+/// send_command(&node, Command::custom(Control::Transpose(-12)))?;
+/// // in the processor
+/// for control in messages.commands.iter().filter_map(Command::to_custom::<Control>) { ... }
+/// ```
+pub trait CustomMessage: Sized {
+    /// The `id` and `value` of the custom variant.
+    fn into_custom(self) -> (u32, f64);
+    /// The message with `id` and `value`, `None` for the ids of other messages.
+    fn from_custom(id: u32, value: f64) -> Option<Self>;
+}
+
+impl Command {
+    /// The custom command carrying `message`.
+    pub fn custom(message: impl CustomMessage) -> Self {
+        let (id, value) = message.into_custom();
+        Command::Custom { id, value }
+    }
+
+    /// The message carried by a custom command, `None` for other commands and messages.
+    pub fn to_custom<M: CustomMessage>(&self) -> Option<M> {
+        match *self {
+            Command::Custom { id, value } => M::from_custom(id, value),
+            _ => None,
+        }
+    }
+}
+
+impl Notification {
+    /// The custom notification carrying `message`.
+    pub fn custom(message: impl CustomMessage) -> Self {
+        let (id, value) = message.into_custom();
+        Notification::Custom { id, value }
+    }
+
+    /// The message carried by a custom notification, `None` for other notifications and messages.
+    pub fn to_custom<M: CustomMessage>(&self) -> Option<M> {
+        match *self {
+            Notification::Custom { id, value } => M::from_custom(id, value),
+            _ => None,
+        }
+    }
+}
+
+/// The capacity of a [`CommandQueue`] created with `default()`.
+pub const DEFAULT_COMMAND_QUEUE_CAPACITY: usize = 128;
+
+/// The capacity of a [`NotificationQueue`] created with `default()`.
+pub const DEFAULT_NOTIFICATION_QUEUE_CAPACITY: usize = 128;
+
+/// Commands waiting for the next block.
+pub type CommandQueue = MessageQueue<Command>;
+
+/// Notifications waiting to be sent.
+pub type NotificationQueue = MessageQueue<Notification>;
+
+/// Messages waiting to be delivered, with a fixed capacity so the audio thread never allocates.
+///
+/// Messages beyond the capacity are dropped and counted.
+#[derive(Clone, Debug)]
+pub struct MessageQueue<M> {
+    messages: Vec<M>,
+    capacity: usize,
+    dropped_count: u64,
+}
+
+impl<M> MessageQueue<M> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            messages: Vec::with_capacity(capacity),
+            capacity,
+            dropped_count: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// The number of messages dropped because the queue was full.
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped_count
+    }
+
+    /// Queues a message, returns `false` when it was dropped.
+    #[inline]
+    pub fn push(&mut self, message: M) -> bool {
+        if self.messages.len() >= self.capacity {
+            self.dropped_count += 1;
+            return false;
+        }
+        self.messages.push(message);
+        true
+    }
+
+    /// The queued messages, in the order they were sent.
+    pub fn as_slice(&self) -> &[M] {
+        &self.messages
+    }
+
+    /// Removes and returns the queued messages, in order.
+    pub fn drain(&mut self) -> impl Iterator<Item = M> + '_ {
+        self.messages.drain(..)
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+}
+
+impl Default for CommandQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMMAND_QUEUE_CAPACITY)
+    }
+}
+
+impl Default for NotificationQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_NOTIFICATION_QUEUE_CAPACITY)
+    }
+}
+
+/// The commands received since the previous block and the notifications to send after this one.
+pub struct Messages<'a> {
+    pub commands: &'a [Command],
+    pub(crate) notifications: &'a mut NotificationQueue,
+}
+
+impl<'a> Messages<'a> {
+    #[inline]
+    pub fn new(commands: &'a [Command], notifications: &'a mut NotificationQueue) -> Self {
+        Self { commands, notifications }
+    }
+
+    /// Queues a notification, sent to the node once the block is processed. Notifications beyond the capacity of the
+    /// queue are dropped.
+    #[inline]
+    pub fn notify(&mut self, notification: Notification) {
+        self.notifications.push(notification);
+    }
+
+    /// Borrows the same commands and notification queue, e.g. for a nested processor.
+    #[inline]
+    pub fn reborrow(&mut self) -> Messages<'_> {
+        Messages { commands: self.commands, notifications: self.notifications }
+    }
+}
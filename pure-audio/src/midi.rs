@@ -0,0 +1,188 @@
+use crate::{Event, EventKind};
+
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+const POLYPHONIC_KEY_PRESSURE: u8 = 0xA0;
+const CONTROL_CHANGE: u8 = 0xB0;
+const PROGRAM_CHANGE: u8 = 0xC0;
+const CHANNEL_PRESSURE: u8 = 0xD0;
+const PITCH_BEND: u8 = 0xE0;
+
+const SYSEX_START: u8 = 0xF0;
+const SYSEX_END: u8 = 0xF7;
+const REALTIME: u8 = 0xF8;
+
+// release velocity used when a NoteOn with velocity 0 is turned into a NoteOff
+const DEFAULT_RELEASE_VELOCITY: u8 = 64;
+
+/// A complete message decoded by a [`MidiParser`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MidiMessage<'a> {
+    Event(Event),
+    /// A SysEx message from `F0` to `F7`, without the realtime bytes received in between.
+    SysEx(&'a [u8]),
+}
+
+/// Decodes a MIDI 1.0 byte stream into channel voice events.
+///
+/// Handles running status, NoteOn with velocity 0 as NoteOff and realtime bytes anywhere in the stream.
+/// System common messages are skipped, and so is SysEx unless the parser is created [`with_sysex`](Self::with_sysex).
+#[derive(Default, Clone, Debug)]
+pub struct MidiParser {
+    running_status: Option<u8>,
+    data: [u8; 2],
+    data_len: usize,
+    in_sysex: bool,
+    // remaining data bytes of a system common message
+    skip: usize,
+    // the SysEx message being received, reserved up front so the parser doesn't allocate
+    sysex: Vec<u8>,
+    max_sysex_length: usize,
+    sysex_overflow: bool,
+}
+
+impl MidiParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A parser that also decodes SysEx messages of up to `max_length` bytes, including `F0` and `F7`. Longer
+    /// messages are dropped.
+    pub fn with_sysex(max_length: usize) -> Self {
+        Self {
+            sysex: Vec::with_capacity(max_length),
+            max_sysex_length: max_length,
+            ..Self::default()
+        }
+    }
+
+    /// Feeds a single byte, returning an event once a message is complete.
+    pub fn push(&mut self, byte: u8, offset: usize) -> Option<Event> {
+        match self.push_message(byte, offset) {
+            Some(MidiMessage::Event(event)) => Some(event),
+            _ => None,
+        }
+    }
+
+    /// Feeds a single byte, returning an event or SysEx message once it is complete. A SysEx message interrupted by
+    /// a status byte other than realtime or `F7` is dropped.
+    pub fn push_message(&mut self, byte: u8, offset: usize) -> Option<MidiMessage<'_>> {
+        if byte >= REALTIME {
+            // realtime bytes may appear anywhere and don't affect the parser state
+            return None;
+        }
+
+        if byte & 0x80 != 0 {
+            let sysex_complete = byte == SYSEX_END && self.in_sysex && self.collect_sysex(byte);
+            self.push_status(byte);
+            return sysex_complete.then_some(MidiMessage::SysEx(&self.sysex));
+        }
+
+        if self.in_sysex {
+            self.collect_sysex(byte);
+            return None;
+        }
+
+        if self.skip > 0 {
+            self.skip -= 1;
+            return None;
+        }
+
+        let status = self.running_status?;
+        self.data[self.data_len] = byte;
+        self.data_len += 1;
+        if self.data_len < data_length(status) {
+            return None;
+        }
+
+        self.data_len = 0;
+        let [data1, data2] = self.data;
+        let channel = status & 0x0F;
+        let kind = match status & 0xF0 {
+            NOTE_OFF => EventKind::NoteOff { key: data1, velocity: data2 },
+            NOTE_ON if data2 == 0 => EventKind::NoteOff { key: data1, velocity: DEFAULT_RELEASE_VELOCITY },
+            NOTE_ON => EventKind::NoteOn { key: data1, velocity: data2 },
+            POLYPHONIC_KEY_PRESSURE => EventKind::PolyphonicKeyPressure { key: data1, pressure: data2 },
+            CONTROL_CHANGE => EventKind::ControlChange { controller: data1, value: data2 },
+            PROGRAM_CHANGE => EventKind::ProgramChange { program: data1 },
+            CHANNEL_PRESSURE => EventKind::ChannelPressure { pressure: data1 },
+            _ => EventKind::PitchBend { value: data1 as u16 | (data2 as u16) << 7 },
+        };
+        Some(MidiMessage::Event(Event::new(offset, channel, kind)))
+    }
+
+    /// Decodes all events in `bytes`, every event gets the same `offset`.
+    pub fn parse<'a>(&'a mut self, bytes: &'a [u8], offset: usize) -> impl Iterator<Item = Event> + 'a {
+        bytes.iter().filter_map(move |&byte| self.push(byte, offset))
+    }
+
+    /// Decodes all events and SysEx messages in `bytes` and passes them to `f`, every event gets the same `offset`.
+    pub fn parse_messages(&mut self, bytes: &[u8], offset: usize, mut f: impl FnMut(MidiMessage)) {
+        for &byte in bytes {
+            if let Some(message) = self.push_message(byte, offset) {
+                f(message);
+            }
+        }
+    }
+
+    fn push_status(&mut self, status: u8) {
+        // any status byte other than realtime ends a SysEx message
+        self.in_sysex = false;
+        self.data_len = 0;
+        self.skip = 0;
+
+        if status < SYSEX_START {
+            self.running_status = Some(status);
+            return;
+        }
+
+        // system common messages cancel running status
+        self.running_status = None;
+        match status {
+            SYSEX_START => {
+                self.in_sysex = true;
+                self.sysex.clear();
+                self.sysex_overflow = false;
+                self.collect_sysex(status);
+            }
+            // MIDI time code quarter frame, song select
+            0xF1 | 0xF3 => self.skip = 1,
+            // song position pointer
+            0xF2 => self.skip = 2,
+            _ => {}
+        }
+    }
+
+    // keeps a byte of the SysEx message, returns false once the message doesn't fit
+    fn collect_sysex(&mut self, byte: u8) -> bool {
+        if self.sysex.len() < self.max_sysex_length {
+            self.sysex.push(byte);
+        } else {
+            self.sysex_overflow = true;
+        }
+        !self.sysex_overflow
+    }
+}
+
+/// Encodes an event as a MIDI 1.0 message without running status.
+pub fn encode<'a>(event: &Event, buffer: &'a mut [u8; 3]) -> &'a [u8] {
+    let channel = event.channel & 0x0F;
+    let (status, data1, data2) = match event.kind {
+        EventKind::NoteOn { key, velocity } => (NOTE_ON, key, velocity),
+        EventKind::NoteOff { key, velocity } => (NOTE_OFF, key, velocity),
+        EventKind::PolyphonicKeyPressure { key, pressure } => (POLYPHONIC_KEY_PRESSURE, key, pressure),
+        EventKind::ControlChange { controller, value } => (CONTROL_CHANGE, controller, value),
+        EventKind::ProgramChange { program } => (PROGRAM_CHANGE, program, 0),
+        EventKind::ChannelPressure { pressure } => (CHANNEL_PRESSURE, pressure, 0),
+        EventKind::PitchBend { value } => (PITCH_BEND, (value & 0x7F) as u8, ((value >> 7) & 0x7F) as u8),
+    };
+    *buffer = [status | channel, data1 & 0x7F, data2 & 0x7F];
+    &buffer[..1 + data_length(status)]
+}
+
+fn data_length(status: u8) -> usize {
+    match status & 0xF0 {
+        PROGRAM_CHANGE | CHANNEL_PRESSURE => 1,
+        _ => 2,
+    }
+}
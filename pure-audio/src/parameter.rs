@@ -1,8 +1,12 @@
-use std::fmt::Display;
+use std::{fmt::Display, marker::PhantomData, ops::Deref};
 
-pub trait FromParameters {
+use crate::DEFAULT_BLOCK_SIZE;
+
+pub trait FromParameters<const BLOCK_SIZE: usize> {
+    /// The argument passed to the processor, which may borrow the parameter values of the block.
+    type Value<'a>;
     const DESCRIPTOR: ParameterDescriptor;
-    fn from_parameters(parameters: &[f32], index: usize) -> Self;
+    fn from_parameters(parameters: &[[f32; BLOCK_SIZE]], index: usize) -> Self::Value<'_>;
 }
 
 #[derive(Copy, Clone)]
@@ -14,8 +18,8 @@ pub enum ParameterAutomationRate {
 impl Display for ParameterAutomationRate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ParameterAutomationRate::A => write!(f, "{}", "a-rate"),
-            ParameterAutomationRate::K => write!(f, "{}", "k-rate"),
+            ParameterAutomationRate::A => write!(f, "a-rate"),
+            ParameterAutomationRate::K => write!(f, "k-rate"),
         }
     }
 }
@@ -29,16 +33,159 @@ pub struct ParameterDescriptor {
     pub automation_rate: ParameterAutomationRate
 }
 
+/// A parameter taken by processors as its plain value when declared [`ParameterAutomationRate::K`], or as [`AudioRate`]
+/// when declared [`ParameterAutomationRate::A`]. The declared rate is the one registered, taking a parameter the other
+/// way fails to compile:
+///
+/// ```compile_fail
+/// use pure_audio::{FromParameters, ParameterAutomationRate, ParameterDescriptor, ProcessorParameter};
+///
+/// struct Volume(f32);
+///
+/// impl ProcessorParameter for Volume {
+///     const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+///         automation_rate: ParameterAutomationRate::A,
+///         default_value: 1.0,
+///         max_value: 1.0,
+///         min_value: 0.0,
+///         name: "Volume",
+///     };
+///
+///     fn from_parameter(value: f32) -> Self {
+///         Volume(value)
+///     }
+/// }
+///
+/// let _ = <Volume as FromParameters<128>>::DESCRIPTOR;
+/// ```
 pub trait ProcessorParameter {
     const DESCRIPTOR: ParameterDescriptor;
     fn from_parameter(value: f32) -> Self;
 }
 
-impl<P: ProcessorParameter> FromParameters for P {
-    const DESCRIPTOR: ParameterDescriptor = P::DESCRIPTOR;
+// k-rate: the first value of the block applies to every sample. An a-rate descriptor fails to compile rather than
+// registering a parameter that is never read per sample
+impl<P: ProcessorParameter, const BLOCK_SIZE: usize> FromParameters<BLOCK_SIZE> for P {
+    type Value<'a> = P;
+
+    const DESCRIPTOR: ParameterDescriptor = {
+        assert!(
+            matches!(P::DESCRIPTOR.automation_rate, ParameterAutomationRate::K),
+            "a-rate parameters are taken as AudioRate, declare plain parameters as k-rate"
+        );
+        P::DESCRIPTOR
+    };
 
     #[inline]
-    fn from_parameters(parameters: &[f32], index: usize) -> Self {
-        P::from_parameter(parameters[index])
+    fn from_parameters(parameters: &[[f32; BLOCK_SIZE]], index: usize) -> Self {
+        P::from_parameter(parameters[index][0])
     }    
-}
\ No newline at end of file
+}
+
+/// Per-sample values of an a-rate parameter for the current block, borrowed from the block.
+///
+/// The descriptor of `P` must be declared [`ParameterAutomationRate::A`], see [`ProcessorParameter`].
+#[derive(Copy, Clone)]
+pub struct AudioRate<'a, P, const BLOCK_SIZE: usize = DEFAULT_BLOCK_SIZE> {
+    values: &'a [f32; BLOCK_SIZE],
+    marker: PhantomData<P>,
+}
+
+impl<'a, P: ProcessorParameter, const BLOCK_SIZE: usize> AudioRate<'a, P, BLOCK_SIZE> {
+    #[inline]
+    pub fn new(values: &'a [f32; BLOCK_SIZE]) -> Self {
+        Self {
+            values,
+            marker: PhantomData,
+        }
+    }
+
+    /// Iterates over the parameter value of every sample in the block.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = P> + '_ {
+        self.values.iter().map(|&value| P::from_parameter(value))
+    }
+}
+
+impl<P, const BLOCK_SIZE: usize> Deref for AudioRate<'_, P, BLOCK_SIZE> {
+    type Target = [f32; BLOCK_SIZE];
+
+    fn deref(&self) -> &Self::Target {
+        self.values
+    }
+}
+
+impl<P: ProcessorParameter, const BLOCK_SIZE: usize> FromParameters<BLOCK_SIZE> for AudioRate<'_, P, BLOCK_SIZE> {
+    type Value<'a> = AudioRate<'a, P, BLOCK_SIZE>;
+
+    const DESCRIPTOR: ParameterDescriptor = {
+        assert!(
+            matches!(P::DESCRIPTOR.automation_rate, ParameterAutomationRate::A),
+            "k-rate parameters are taken as plain values, declare AudioRate parameters as a-rate"
+        );
+        P::DESCRIPTOR
+    };
+
+    #[inline]
+    fn from_parameters(parameters: &[[f32; BLOCK_SIZE]], index: usize) -> Self::Value<'_> {
+        AudioRate::new(&parameters[index])
+    }
+}
+
+/// Whether the parameters of a processor, the `Params` of [`IntoProcessor`](crate::IntoProcessor), include `P`, for
+/// checks at compile time. Parameters are compared by name.
+pub trait HasParameter<P> {
+    const HAS_PARAMETER: bool;
+}
+
+impl<P: ProcessorParameter, Q: ProcessorParameter> HasParameter<P> for Q {
+    const HAS_PARAMETER: bool = same_name(P::DESCRIPTOR.name, Q::DESCRIPTOR.name);
+}
+
+impl<P: ProcessorParameter, Q: ProcessorParameter, const BLOCK_SIZE: usize> HasParameter<P>
+    for AudioRate<'_, Q, BLOCK_SIZE>
+{
+    const HAS_PARAMETER: bool = same_name(P::DESCRIPTOR.name, Q::DESCRIPTOR.name);
+}
+
+// processors take tuples of parameters, graphs pairs of the parameters of their processors
+macro_rules! impl_has_parameter {
+    ($($param:ident),*) => {
+        impl<P, $($param: HasParameter<P>),*> HasParameter<P> for ($($param,)*) {
+            const HAS_PARAMETER: bool = false $(|| $param::HAS_PARAMETER)*;
+        }
+    };
+}
+
+impl_has_parameter!();
+impl_has_parameter!(P1);
+impl_has_parameter!(P1, P2);
+impl_has_parameter!(P1, P2, P3);
+impl_has_parameter!(P1, P2, P3, P4);
+impl_has_parameter!(P1, P2, P3, P4, P5);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8, P9);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8, P9, P10);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13, P14);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13, P14, P15);
+impl_has_parameter!(P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13, P14, P15, P16);
+
+const fn same_name(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
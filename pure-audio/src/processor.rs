@@ -1,5 +1,6 @@
 use crate::{
-    event::Event, EffectAudioData, FromParameters, InputBuffer, InstrumentAudioData, OutputBuffer, ParameterDescriptor
+    event::Event, message::Messages, EffectAudioData, FromParameters, InputBuffer, InstrumentAudioData, OutputBuffer,
+    ParameterDescriptor
 };
 use std::marker::PhantomData;
 
@@ -17,10 +18,10 @@ pub trait Processor<
         &mut self,
         inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
         outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
-        parameters: &[f32; NUM_PARAMS],
-        events: &[Event]
-    ) {
-    }
+        parameters: &[[f32; BLOCK_SIZE]; NUM_PARAMS],
+        events: &[Event],
+        messages: &mut Messages
+    );
 }
 
 pub struct ProcessorWrapper<
@@ -99,297 +100,170 @@ pub trait IntoProcessor<
     >;
 }
 
-// effect with 1 parameter
-impl<
-        F,
-        P1,
-        const IS_INSTRUMENT: bool,
-        const NUM_INPUTS: usize,
-        const NUM_OUTPUTS: usize,
-        const NUM_CHANNELS: usize,
-        const BLOCK_SIZE: usize,
-        S,
-    > Processor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 1, (P1,)>
-    for ProcessorWrapper<F, false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 1, (P1,), S>
-where
-    F: 'static + FnMut(EffectAudioData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, P1),
-    P1: 'static + FromParameters,
-    S: 'static + Default,
-{
-    #[inline]
-    fn process(
-        &mut self,
-        inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
-        outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
-        parameters: &[f32; 1],
-        events: &[Event]
-    ) {
-        let p1 = P1::from_parameters(parameters, 0);
-        let data = EffectAudioData {
-            inputs: InputBuffer::new(inputs),
-            outputs: OutputBuffer::new(outputs),
-            sample_rate: self.sample_rate,
-            state: &mut self.state,
-        };
-        (self.f)(data, p1);
-    }
-}
-
-impl<
-        F,
-        P1,
-        const NUM_INPUTS: usize,
-        const NUM_OUTPUTS: usize,
-        const NUM_CHANNELS: usize,
-        const BLOCK_SIZE: usize,
-        S,
-    > IntoProcessor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 1, (P1,), S> for F
-where
-    F: 'static + FnMut(EffectAudioData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, P1),
-    P1: 'static + FromParameters,
-    S: 'static + Default,
-{
-    fn get_parameter_descriptors() -> [ParameterDescriptor; 1] {
-        [P1::DESCRIPTOR]
-    }
-
-    fn into_processor(
-        self,
-        sample_rate: f32,
-    ) -> impl Processor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 1, (P1,)> {
-        ProcessorWrapper::new(self, sample_rate, S::default())
-    }
-}
-
-// effect with 2 parameters
-impl<
-        F,
-        P1,
-        P2,
-        const NUM_INPUTS: usize,
-        const NUM_OUTPUTS: usize,
-        const NUM_CHANNELS: usize,
-        const BLOCK_SIZE: usize,
-        S,
-    > Processor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 2, (P1, P2)>
-    for ProcessorWrapper<
-        F,
-        false,
-        NUM_INPUTS,
-        NUM_OUTPUTS,
-        NUM_CHANNELS,
-        BLOCK_SIZE,
-        2,
-        (P1, P2),
-        S,
-    >
-where
-    F: 'static
-        + FnMut(EffectAudioData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, P1, P2),
-    P1: 'static + FromParameters,
-    P2: 'static + FromParameters,
-    S: 'static + Default,
-{
-    #[inline]
-    fn process(
-        &mut self,
-        inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
-        outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
-        parameters: &[f32; 2],
-        events: &[Event]
-    ) {
-        let p1 = P1::from_parameters(parameters, 0);
-        let p2 = P2::from_parameters(parameters, 1);
-        let data = EffectAudioData {
-            inputs: InputBuffer::new(inputs),
-            outputs: OutputBuffer::new(outputs),
-            sample_rate: self.sample_rate,
-            state: &mut self.state,
-        };
-        (self.f)(data, p1, p2);
-    }
-}
-
-impl<
-        F,
-        P1,
-        P2,
-        const NUM_INPUTS: usize,
-        const NUM_OUTPUTS: usize,
-        const NUM_CHANNELS: usize,
-        const BLOCK_SIZE: usize,
-        S,
-    > IntoProcessor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 2, (P1, P2), S> for F
-where
-    F: 'static
-        + FnMut(EffectAudioData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, P1, P2),
-    P1: 'static + FromParameters,
-    P2: 'static + FromParameters,
-    S: 'static + Default,
-{
-    fn get_parameter_descriptors() -> [ParameterDescriptor; 2] {
-        [P1::DESCRIPTOR, P2::DESCRIPTOR]
-    }
-
-    fn into_processor(
-        self,
-        sample_rate: f32,
-    ) -> impl Processor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 2, (P1, P2)> {
-        ProcessorWrapper::new(self, sample_rate, S::default())
-    }
-}
-
-// instrument with 0 parameters
-impl<F, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize, S>
-    Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 0, ()>
-    for ProcessorWrapper<F, true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 0, (), S>
-where 
-    F: 'static + FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>),
-    S: 'static + Default
-{
-    #[inline]
-    fn process(
-        &mut self,
-        _inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; 0],
-        outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
-        _parameters: &[f32; 0],
-        events: &[Event]
-    ) {
-        let data = InstrumentAudioData {
-            events,
-            outputs: OutputBuffer::new(outputs),
-            sample_rate: self.sample_rate,
-            state: &mut self.state
-        };
-        (self.f)(data)
-    }
-}
+macro_rules! impl_processor {
+    ($num_params:literal; $($param:ident: $index:literal),*) => {
+        // effect with $num_params parameters
+        impl<
+                F,
+                $($param,)*
+                const NUM_INPUTS: usize,
+                const NUM_OUTPUTS: usize,
+                const NUM_CHANNELS: usize,
+                const BLOCK_SIZE: usize,
+                S,
+            > Processor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*)>
+            for ProcessorWrapper<
+                F,
+                false,
+                NUM_INPUTS,
+                NUM_OUTPUTS,
+                NUM_CHANNELS,
+                BLOCK_SIZE,
+                $num_params,
+                ($($param,)*),
+                S,
+            >
+        where
+            // the parameter types as written pick the impl, the values borrowed from the block are passed
+            F: 'static
+                + FnMut(EffectAudioData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param),*)
+                + for<'p> FnMut(EffectAudioData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param::Value<'p>),*),
+            $($param: 'static + FromParameters<BLOCK_SIZE>,)*
+            S: 'static + Default,
+        {
+            #[inline]
+            #[allow(unused_variables)]
+            fn process(
+                &mut self,
+                inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
+                outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+                parameters: &[[f32; BLOCK_SIZE]; $num_params],
+                _events: &[Event],
+                messages: &mut Messages
+            ) {
+                let data = EffectAudioData {
+                    inputs: InputBuffer::new(inputs),
+                    outputs: OutputBuffer::new(outputs),
+                    sample_rate: self.sample_rate,
+                    state: &mut self.state,
+                    messages: messages.reborrow(),
+                };
+                (self.f)(data, $($param::from_parameters(parameters, $index)),*);
+            }
+        }
 
-impl<F, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize, S>
-    IntoProcessor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 0, (), S> for F
-where
-    F: 'static + FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>),
-    S: 'static + Default,
-{
-    fn get_parameter_descriptors() -> [ParameterDescriptor; 0] {
-        []
-    }
+        impl<
+                F,
+                $($param,)*
+                const NUM_INPUTS: usize,
+                const NUM_OUTPUTS: usize,
+                const NUM_CHANNELS: usize,
+                const BLOCK_SIZE: usize,
+                S,
+            > IntoProcessor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*), S> for F
+        where
+            // the parameter types as written pick the impl, the values borrowed from the block are passed
+            F: 'static
+                + FnMut(EffectAudioData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param),*)
+                + for<'p> FnMut(EffectAudioData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param::Value<'p>),*),
+            $($param: 'static + FromParameters<BLOCK_SIZE>,)*
+            S: 'static + Default,
+        {
+            fn get_parameter_descriptors() -> [ParameterDescriptor; $num_params] {
+                [$($param::DESCRIPTOR),*]
+            }
 
-    fn into_processor(
-        self,
-        sample_rate: f32,
-    ) -> impl Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 0, ()> {
-        ProcessorWrapper::new(self, sample_rate, S::default())
-    }
-}
+            fn into_processor(
+                self,
+                sample_rate: f32,
+            ) -> impl Processor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*)> {
+                ProcessorWrapper::new(self, sample_rate, S::default())
+            }
+        }
 
-// instrument with 1 parameter
-impl<F, P1, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize, S>
-    Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 1, (P1,)>
-    for ProcessorWrapper<F, true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 1, (P1,), S>
-where
-    F: 'static + FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, P1),
-    P1: 'static + FromParameters,
-    S: 'static + Default,
-{
-    #[inline]
-    fn process(
-        &mut self,
-        _inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; 0],
-        outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
-        parameters: &[f32; 1],
-        events: &[Event]
-    ) {
-        let p1 = P1::from_parameters(parameters, 0);
-        let data = InstrumentAudioData {
-            events,
-            outputs: OutputBuffer::new(outputs),
-            sample_rate: self.sample_rate,
-            state: &mut self.state,
-        };
-        (self.f)(data, p1);
-    }
-}
+        // instrument with $num_params parameters
+        impl<
+                F,
+                $($param,)*
+                const NUM_OUTPUTS: usize,
+                const NUM_CHANNELS: usize,
+                const BLOCK_SIZE: usize,
+                S,
+            > Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*)>
+            for ProcessorWrapper<F, true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*), S>
+        where
+            // the parameter types as written pick the impl, the values borrowed from the block are passed
+            F: 'static
+                + FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param),*)
+                + for<'p> FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param::Value<'p>),*),
+            $($param: 'static + FromParameters<BLOCK_SIZE>,)*
+            S: 'static + Default,
+        {
+            #[inline]
+            #[allow(unused_variables)]
+            fn process(
+                &mut self,
+                _inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; 0],
+                outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+                parameters: &[[f32; BLOCK_SIZE]; $num_params],
+                events: &[Event],
+                messages: &mut Messages
+            ) {
+                let data = InstrumentAudioData {
+                    events,
+                    outputs: OutputBuffer::new(outputs),
+                    sample_rate: self.sample_rate,
+                    state: &mut self.state,
+                    messages: messages.reborrow(),
+                };
+                (self.f)(data, $($param::from_parameters(parameters, $index)),*);
+            }
+        }
 
-impl<F, P1, const NUM_OUTPUTS: usize, const NUM_CHANNELS: usize, const BLOCK_SIZE: usize, S>
-    IntoProcessor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 1, (P1,), S> for F
-where
-    F: 'static + FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, P1),
-    P1: 'static + FromParameters,
-    S: 'static + Default,
-{
-    fn get_parameter_descriptors() -> [ParameterDescriptor; 1] {
-        [P1::DESCRIPTOR]
-    }
+        impl<
+                F,
+                $($param,)*
+                const NUM_OUTPUTS: usize,
+                const NUM_CHANNELS: usize,
+                const BLOCK_SIZE: usize,
+                S,
+            > IntoProcessor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*), S> for F
+        where
+            // the parameter types as written pick the impl, the values borrowed from the block are passed
+            F: 'static
+                + FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param),*)
+                + for<'p> FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param::Value<'p>),*),
+            $($param: 'static + FromParameters<BLOCK_SIZE>,)*
+            S: 'static + Default,
+        {
+            fn get_parameter_descriptors() -> [ParameterDescriptor; $num_params] {
+                [$($param::DESCRIPTOR),*]
+            }
 
-    fn into_processor(
-        self,
-        sample_rate: f32,
-    ) -> impl Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 1, (P1,)> {
-        ProcessorWrapper::new(self, sample_rate, S::default())
-    }
-}
-
-// instrument with 2 parameters
-impl<
-        F,
-        P1,
-        P2,
-        const NUM_OUTPUTS: usize,
-        const NUM_CHANNELS: usize,
-        const BLOCK_SIZE: usize,
-        S,
-    > Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 2, (P1, P2)>
-    for ProcessorWrapper<F, true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 2, (P1, P2), S>
-where
-    F: 'static + FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, P1, P2),
-    P1: 'static + FromParameters,
-    P2: 'static + FromParameters,
-    S: 'static + Default,
-{
-    #[inline]
-    fn process(
-        &mut self,
-        _inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; 0],
-        outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
-        parameters: &[f32; 2],
-        events: &[Event]
-    ) {
-        let p1 = P1::from_parameters(parameters, 0);
-        let p2 = P2::from_parameters(parameters, 1);
-        let data = InstrumentAudioData {
-            events,
-            outputs: OutputBuffer::new(outputs),
-            sample_rate: self.sample_rate,
-            state: &mut self.state,
-        };
-        (self.f)(data, p1, p2);
-    }
+            fn into_processor(
+                self,
+                sample_rate: f32,
+            ) -> impl Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*)> {
+                ProcessorWrapper::new(self, sample_rate, S::default())
+            }
+        }
+    };
 }
 
-impl<
-        F,
-        P1,
-        P2,
-        const NUM_OUTPUTS: usize,
-        const NUM_CHANNELS: usize,
-        const BLOCK_SIZE: usize,
-        S,
-    > IntoProcessor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 2, (P1, P2), S> for F
-where
-    F: 'static + FnMut(InstrumentAudioData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, P1, P2),
-    P1: 'static + FromParameters,
-    P2: 'static + FromParameters,
-    S: 'static + Default,
-{
-    fn get_parameter_descriptors() -> [ParameterDescriptor; 2] {
-        [P1::DESCRIPTOR, P2::DESCRIPTOR]
-    }
-
-    fn into_processor(
-        self,
-        sample_rate: f32,
-    ) -> impl Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, 2, (P1, P2)> {
-        ProcessorWrapper::new(self, sample_rate, S::default())
-    }
-}
+impl_processor!(0;);
+impl_processor!(1; P1: 0);
+impl_processor!(2; P1: 0, P2: 1);
+impl_processor!(3; P1: 0, P2: 1, P3: 2);
+impl_processor!(4; P1: 0, P2: 1, P3: 2, P4: 3);
+impl_processor!(5; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4);
+impl_processor!(6; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5);
+impl_processor!(7; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6);
+impl_processor!(8; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7);
+impl_processor!(9; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8);
+impl_processor!(10; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9);
+impl_processor!(11; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10);
+impl_processor!(12; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11);
+impl_processor!(13; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11, P13: 12);
+impl_processor!(14; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11, P13: 12, P14: 13);
+impl_processor!(15; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11, P13: 12, P14: 13, P15: 14);
+impl_processor!(16; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11, P13: 12, P14: 13, P15: 14, P16: 15);
@@ -0,0 +1,103 @@
+use crate::{IntoProcessor, Messages, NotificationQueue, Processor, TimedEvent};
+
+/// Sets parameter `index` to `value` from `frame` on, until the next change.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ParameterChange {
+    pub frame: usize,
+    pub index: usize,
+    pub value: f32,
+}
+
+/// Renders `num_frames` frames offline by running the processor block by block.
+///
+/// Inputs shorter than `num_frames` are padded with silence. Parameters start at their default value and are clamped
+/// to their range, as in Web Audio. Events are at absolute frames and are delivered at their offset within the block they fall in.
+/// There are no commands and notifications are discarded.
+///
+/// # Panics
+///
+/// Panics if the index of a parameter change is not below the number of parameters of the processor.
+pub fn render<
+    F,
+    const IS_INSTRUMENT: bool,
+    const NUM_INPUTS: usize,
+    const NUM_OUTPUTS: usize,
+    const NUM_CHANNELS: usize,
+    const BLOCK_SIZE: usize,
+    const NUM_PARAMS: usize,
+    Params,
+    S,
+>(
+    process: F,
+    sample_rate: f32,
+    num_frames: usize,
+    inputs: &[[&[f32]; NUM_CHANNELS]; NUM_INPUTS],
+    parameters: &[ParameterChange],
+    events: &[TimedEvent],
+) -> [[Vec<f32>; NUM_CHANNELS]; NUM_OUTPUTS]
+where
+    F: IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, Params, S>,
+{
+    let descriptors = F::get_parameter_descriptors();
+    let mut processor = process.into_processor(sample_rate);
+
+    if let Some(change) = parameters.iter().find(|change| change.index >= NUM_PARAMS) {
+        panic!("parameter change at frame {} sets parameter {} of {NUM_PARAMS}", change.frame, change.index);
+    }
+
+    // stable sorts keep the given order of changes and events on the same frame
+    let mut parameters = parameters.to_vec();
+    parameters.sort_by_key(|change| change.frame);
+    let mut parameters = parameters.into_iter().peekable();
+    let mut events = events.to_vec();
+    events.sort_by_key(|event| event.frame);
+    let mut events = events.into_iter().peekable();
+
+    let mut values = descriptors.map(|descriptor| descriptor.default_value);
+    let mut input_block = [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS];
+    let mut parameter_block = [[0.0; BLOCK_SIZE]; NUM_PARAMS];
+    let mut block_events = vec![];
+    let mut notifications = NotificationQueue::default();
+    let mut outputs = std::array::from_fn(|_| std::array::from_fn(|_| vec![0.0; num_frames]));
+
+    for start in (0..num_frames).step_by(BLOCK_SIZE) {
+        let length = BLOCK_SIZE.min(num_frames - start);
+
+        for (block, input) in input_block.iter_mut().zip(inputs) {
+            for (block_channel, channel) in block.iter_mut().zip(input) {
+                let samples = channel.get(start..).unwrap_or_default();
+                let copied = samples.len().min(length);
+                block_channel[..copied].copy_from_slice(&samples[..copied]);
+                block_channel[copied..].fill(0.0);
+            }
+        }
+
+        for frame in 0..BLOCK_SIZE {
+            while let Some(change) = parameters.next_if(|change| change.frame <= start + frame) {
+                let descriptor = &descriptors[change.index];
+                values[change.index] = change.value.clamp(descriptor.min_value, descriptor.max_value);
+            }
+            for (block, value) in parameter_block.iter_mut().zip(values) {
+                block[frame] = value;
+            }
+        }
+
+        block_events.clear();
+        while let Some(event) = events.next_if(|event| event.frame < (start + BLOCK_SIZE) as u64) {
+            block_events.push(event.at_offset(event.frame.saturating_sub(start as u64) as usize));
+        }
+
+        let mut output_block = [[[0.0; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS];
+        notifications.clear();
+        let mut messages = Messages::new(&[], &mut notifications);
+        processor.process(&input_block, &mut output_block, &parameter_block, &block_events, &mut messages);
+
+        for (output, block) in outputs.iter_mut().zip(&output_block) {
+            for (channel, block_channel) in output.iter_mut().zip(block) {
+                channel[start..start + length].copy_from_slice(&block_channel[..length]);
+            }
+        }
+    }
+
+    outputs
+}
@@ -0,0 +1,227 @@
+use crate::{
+    event::Event, message::Messages, EffectSliceData, InputSlices, InstrumentSliceData, IntoProcessor, OutputSlices,
+    ParameterDescriptor, Processor, ProcessorParameter,
+};
+use std::marker::PhantomData;
+
+/// Splits every block at event and parameter change boundaries and calls the wrapped function once per sub-block, so
+/// note-ons and parameter jumps land on the exact frame.
+///
+/// The function takes [`EffectSliceData`] or [`InstrumentSliceData`], its parameters are constant within a sub-block.
+/// Audio rate automation changes every frame and therefore results in a call per frame.
+///
+/// ```ignore
+/// create_wasm_processor(SampleAccurate(process), sample_rate)
+/// ```
+#[derive(Copy, Clone)]
+pub struct SampleAccurate<F>(pub F);
+
+struct SampleAccurateProcessor<F, const BLOCK_SIZE: usize, Params, S> {
+    f: F,
+    sample_rate: f32,
+    state: S,
+    // events of the current sub-block, rebased to offset 0
+    events: Vec<Event>,
+    marker: PhantomData<Params>,
+}
+
+impl<F, const BLOCK_SIZE: usize, Params, S: Default> SampleAccurateProcessor<F, BLOCK_SIZE, Params, S> {
+    fn new(f: F, sample_rate: f32) -> Self {
+        Self {
+            f,
+            sample_rate,
+            state: S::default(),
+            events: Vec::with_capacity(BLOCK_SIZE),
+            marker: PhantomData,
+        }
+    }
+}
+
+// calls `process` with the frame range and the events of every sub-block
+#[inline]
+fn for_each_sub_block<const BLOCK_SIZE: usize, const NUM_PARAMS: usize>(
+    parameters: &[[f32; BLOCK_SIZE]; NUM_PARAMS],
+    mut events: &[Event],
+    buffer: &mut Vec<Event>,
+    mut process: impl FnMut(usize, usize, &[Event]),
+) {
+    let mut start = 0;
+    while start < BLOCK_SIZE {
+        // events are sorted by offset, late events apply to the last frame
+        let count = events.partition_point(|event| event.offset.min(BLOCK_SIZE - 1) <= start);
+        buffer.clear();
+        buffer.extend(events[..count].iter().map(|event| Event { offset: 0, ..*event }));
+        events = &events[count..];
+
+        let next_event = events.first().map_or(BLOCK_SIZE, |event| event.offset.min(BLOCK_SIZE - 1));
+        let end = (start + 1..next_event)
+            .find(|frame| parameters.iter().any(|values| values[*frame] != values[frame - 1]))
+            .unwrap_or(next_event);
+
+        process(start, end, buffer);
+        start = end;
+    }
+}
+
+// commands are delivered once, with the first sub-block
+#[inline]
+fn sub_block_messages<'a>(messages: &'a mut Messages, start: usize) -> Messages<'a> {
+    let commands = if start == 0 { messages.commands } else { &[] };
+    Messages::new(commands, messages.notifications)
+}
+
+macro_rules! impl_sample_accurate {
+    ($num_params:literal; $($param:ident: $index:literal),*) => {
+        // effect with $num_params parameters
+        impl<
+                F,
+                $($param,)*
+                const NUM_INPUTS: usize,
+                const NUM_OUTPUTS: usize,
+                const NUM_CHANNELS: usize,
+                const BLOCK_SIZE: usize,
+                S,
+            > Processor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*)>
+            for SampleAccurateProcessor<F, BLOCK_SIZE, ($($param,)*), S>
+        where
+            F: 'static
+                + FnMut(EffectSliceData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param),*),
+            $($param: 'static + ProcessorParameter,)*
+            S: 'static + Default,
+        {
+            #[inline]
+            fn process(
+                &mut self,
+                inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_INPUTS],
+                outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+                parameters: &[[f32; BLOCK_SIZE]; $num_params],
+                events: &[Event],
+                messages: &mut Messages
+            ) {
+                let Self { f, sample_rate, state, events: buffer, .. } = self;
+                for_each_sub_block(parameters, events, buffer, |start, end, _| {
+                    let data = EffectSliceData {
+                        inputs: InputSlices::new(inputs.each_ref().map(|input| input.each_ref().map(|channel| &channel[start..end]))),
+                        outputs: OutputSlices::new(outputs.each_mut().map(|output| output.each_mut().map(|channel| &mut channel[start..end]))),
+                        sample_rate: *sample_rate,
+                        state,
+                        messages: sub_block_messages(messages, start),
+                        marker: PhantomData,
+                    };
+                    f(data, $($param::from_parameter(parameters[$index][start])),*);
+                });
+            }
+        }
+
+        impl<
+                F,
+                $($param,)*
+                const NUM_INPUTS: usize,
+                const NUM_OUTPUTS: usize,
+                const NUM_CHANNELS: usize,
+                const BLOCK_SIZE: usize,
+                S,
+            > IntoProcessor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*), S>
+            for SampleAccurate<F>
+        where
+            F: 'static
+                + FnMut(EffectSliceData<NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param),*),
+            $($param: 'static + ProcessorParameter,)*
+            S: 'static + Default,
+        {
+            fn get_parameter_descriptors() -> [ParameterDescriptor; $num_params] {
+                [$($param::DESCRIPTOR),*]
+            }
+
+            fn into_processor(
+                self,
+                sample_rate: f32,
+            ) -> impl Processor<false, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*)> {
+                SampleAccurateProcessor::<_, BLOCK_SIZE, _, S>::new(self.0, sample_rate)
+            }
+        }
+
+        // instrument with $num_params parameters
+        impl<
+                F,
+                $($param,)*
+                const NUM_OUTPUTS: usize,
+                const NUM_CHANNELS: usize,
+                const BLOCK_SIZE: usize,
+                S,
+            > Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*)>
+            for SampleAccurateProcessor<F, BLOCK_SIZE, ($($param,)*), S>
+        where
+            F: 'static + FnMut(InstrumentSliceData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param),*),
+            $($param: 'static + ProcessorParameter,)*
+            S: 'static + Default,
+        {
+            #[inline]
+            fn process(
+                &mut self,
+                _inputs: &[[[f32; BLOCK_SIZE]; NUM_CHANNELS]; 0],
+                outputs: &mut [[[f32; BLOCK_SIZE]; NUM_CHANNELS]; NUM_OUTPUTS],
+                parameters: &[[f32; BLOCK_SIZE]; $num_params],
+                events: &[Event],
+                messages: &mut Messages
+            ) {
+                let Self { f, sample_rate, state, events: buffer, .. } = self;
+                for_each_sub_block(parameters, events, buffer, |start, end, events| {
+                    let data = InstrumentSliceData {
+                        events,
+                        outputs: OutputSlices::new(outputs.each_mut().map(|output| output.each_mut().map(|channel| &mut channel[start..end]))),
+                        sample_rate: *sample_rate,
+                        state,
+                        messages: sub_block_messages(messages, start),
+                        marker: PhantomData,
+                    };
+                    f(data, $($param::from_parameter(parameters[$index][start])),*);
+                });
+            }
+        }
+
+        impl<
+                F,
+                $($param,)*
+                const NUM_OUTPUTS: usize,
+                const NUM_CHANNELS: usize,
+                const BLOCK_SIZE: usize,
+                S,
+            > IntoProcessor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*), S>
+            for SampleAccurate<F>
+        where
+            F: 'static + FnMut(InstrumentSliceData<NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, S>, $($param),*),
+            $($param: 'static + ProcessorParameter,)*
+            S: 'static + Default,
+        {
+            fn get_parameter_descriptors() -> [ParameterDescriptor; $num_params] {
+                [$($param::DESCRIPTOR),*]
+            }
+
+            fn into_processor(
+                self,
+                sample_rate: f32,
+            ) -> impl Processor<true, 0, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, $num_params, ($($param,)*)> {
+                SampleAccurateProcessor::<_, BLOCK_SIZE, _, S>::new(self.0, sample_rate)
+            }
+        }
+    };
+}
+
+impl_sample_accurate!(0;);
+impl_sample_accurate!(1; P1: 0);
+impl_sample_accurate!(2; P1: 0, P2: 1);
+impl_sample_accurate!(3; P1: 0, P2: 1, P3: 2);
+impl_sample_accurate!(4; P1: 0, P2: 1, P3: 2, P4: 3);
+impl_sample_accurate!(5; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4);
+impl_sample_accurate!(6; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5);
+impl_sample_accurate!(7; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6);
+impl_sample_accurate!(8; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7);
+impl_sample_accurate!(9; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8);
+impl_sample_accurate!(10; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9);
+impl_sample_accurate!(11; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10);
+impl_sample_accurate!(12; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11);
+impl_sample_accurate!(13; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11, P13: 12);
+impl_sample_accurate!(14; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11, P13: 12, P14: 13);
+impl_sample_accurate!(15; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11, P13: 12, P14: 13, P15: 14);
+impl_sample_accurate!(16; P1: 0, P2: 1, P3: 2, P4: 3, P5: 4, P6: 5, P7: 6, P8: 7, P9: 8, P10: 9, P11: 10, P12: 11, P13: 12, P14: 13, P15: 14, P16: 15);
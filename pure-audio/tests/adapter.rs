@@ -0,0 +1,72 @@
+use pure_audio::{
+    BlockAdapter, EffectAudioData, Event, EventKind, InputBuffer, InstrumentAudioData, IntoProcessor, Messages,
+    NotificationQueue, OutputBuffer,
+};
+
+fn double(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData<1, 1, 1, 64>,
+) {
+    for (input_sample, output_sample) in input.iter().zip(output) {
+        *output_sample = input_sample * 2.0;
+    }
+}
+
+// writes an impulse on the frame of every event
+fn impulses(
+    InstrumentAudioData {
+        events,
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: InstrumentAudioData<1, 1, 64>,
+) {
+    for event in events {
+        output[event.offset] = 1.0;
+    }
+}
+
+#[test]
+fn variable_host_sizes_are_delayed_by_one_block() {
+    let mut adapter = BlockAdapter::new(double.into_processor(48000.0));
+    assert_eq!(adapter.latency(), 64);
+
+    let input = (1..=1000).map(|frame| frame as f32).collect::<Vec<_>>();
+    let mut output = vec![0.0; input.len()];
+    let mut notifications = NotificationQueue::default();
+    let mut start = 0;
+    for size in [1, 37, 64, 200, 480, 5].into_iter().cycle() {
+        let end = (start + size).min(input.len());
+        adapter.process(&[[&input[start..end]]], &mut [[&mut output[start..end]]], &[], &[], &mut Messages::new(&[], &mut notifications));
+        start = end;
+        if start == input.len() {
+            break;
+        }
+    }
+
+    assert!(output[..64].iter().all(|sample| *sample == 0.0));
+    for (input_sample, output_sample) in input.iter().zip(&output[64..]) {
+        assert_eq!(*output_sample, input_sample * 2.0);
+    }
+}
+
+#[test]
+fn events_keep_their_frame() {
+    let mut adapter = BlockAdapter::new(impulses.into_processor(48000.0));
+    let note = EventKind::NoteOn { key: 60, velocity: 100 };
+    let mut output = vec![0.0; 480];
+    let mut notifications = NotificationQueue::default();
+    let mut messages = Messages::new(&[], &mut notifications);
+
+    // events at absolute frames 10, 150 and 250, the last one given before its block starts
+    let (first, rest) = output.split_at_mut(100);
+    adapter.process(&[], &mut [[first]], &[], &[Event::new(10, 0, note)], &mut messages);
+    let (second, third) = rest.split_at_mut(100);
+    adapter.process(&[], &mut [[second]], &[], &[Event::new(50, 0, note), Event::new(150, 0, note)], &mut messages);
+    adapter.process(&[], &mut [[third]], &[], &[], &mut messages);
+
+    let frames = output.iter().enumerate().filter(|(_, sample)| **sample == 1.0).map(|(frame, _)| frame);
+    assert_eq!(frames.collect::<Vec<_>>(), [10 + 64, 150 + 64, 250 + 64]);
+}
@@ -0,0 +1,110 @@
+use pure_audio::{
+    graph::{mix, split, Graph},
+    render, EffectAudioData, InputBuffer, IntoProcessor, OutputBuffer, ParameterAutomationRate, ParameterChange,
+    ParameterDescriptor, ProcessorParameter,
+};
+
+#[derive(Copy, Clone)]
+struct Scale(f32);
+
+impl ProcessorParameter for Scale {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::K,
+        default_value: 1.0,
+        max_value: 4.0,
+        min_value: 0.0,
+        name: "Scale",
+    };
+
+    fn from_parameter(value: f32) -> Self {
+        Scale(value)
+    }
+}
+
+fn scale(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData,
+    Scale(scale): Scale,
+) {
+    for (input_sample, output_sample) in input.iter().zip(output) {
+        *output_sample = input_sample * scale;
+    }
+}
+
+fn identity(
+    EffectAudioData {
+        inputs: InputBuffer(inputs),
+        outputs: OutputBuffer(outputs),
+        ..
+    }: EffectAudioData,
+) {
+    *outputs = *inputs;
+}
+
+fn names<P: IntoProcessor<false, 1, 1, 1, 128, NUM_PARAMS, Params, S>, const NUM_PARAMS: usize, Params, S>(_: &P) -> [&'static str; NUM_PARAMS] {
+    P::get_parameter_descriptors().map(|descriptor| descriptor.name)
+}
+
+fn ramp(num_frames: usize) -> Vec<f32> {
+    (0..num_frames).map(|frame| frame as f32 / num_frames as f32).collect()
+}
+
+#[test]
+fn series_splits_parameters() {
+    let graph = Graph::<2>::series(scale, scale);
+    assert_eq!(names(&graph), ["Scale", "Scale 2"]);
+    // names stay unique in nested graphs
+    assert_eq!(names(&Graph::<3>::series(scale, Graph::<2>::series(scale, scale))), ["Scale", "Scale 3", "Scale 2"]);
+    assert_eq!(names(&Graph::<3>::parallel(Graph::<2>::series(scale, scale), scale)), ["Scale", "Scale 2", "Scale 3"]);
+
+    let input = ramp(300);
+    let parameters = [
+        ParameterChange { frame: 0, index: 0, value: 0.5 },
+        ParameterChange { frame: 0, index: 1, value: 3.0 },
+    ];
+    let [[output]] = render(graph, 48000.0, input.len(), &[[&input]], &parameters, &[]);
+    for (input_sample, output_sample) in input.iter().zip(&output) {
+        assert_eq!(*output_sample, input_sample * 1.5);
+    }
+}
+
+#[test]
+fn parallel_sums_outputs() {
+    let graph = Graph::<1>::parallel(scale, identity);
+    let input = ramp(200);
+    let parameters = [ParameterChange { frame: 0, index: 0, value: 2.0 }];
+    let [[output]] = render(graph, 48000.0, input.len(), &[[&input]], &parameters, &[]);
+    for (input_sample, output_sample) in input.iter().zip(&output) {
+        assert_eq!(*output_sample, input_sample * 3.0);
+    }
+}
+
+#[test]
+fn split_and_mix_route_between_nested_nodes() {
+    // input -> split -> (scale, pass through) -> mix
+    let routed = Graph::<1>::series(split::<2, 1, 128>, Graph::<1>::series(scale_first, mix::<2, 1, 128>));
+    let input = ramp(256);
+    let parameters = [ParameterChange { frame: 0, index: 0, value: 4.0 }];
+    let [[output]] = render(routed, 48000.0, input.len(), &[[&input]], &parameters, &[]);
+    for (input_sample, output_sample) in input.iter().zip(&output) {
+        assert_eq!(*output_sample, input_sample * 5.0);
+    }
+}
+
+// scales the first of two inputs only
+fn scale_first(
+    EffectAudioData {
+        inputs: InputBuffer([first, second]),
+        outputs: OutputBuffer([first_output, second_output]),
+        ..
+    }: EffectAudioData<2, 2>,
+    Scale(scale): Scale,
+) {
+    for (input_sample, output_sample) in first[0].iter().zip(&mut first_output[0]) {
+        *output_sample = input_sample * scale;
+    }
+    *second_output = *second;
+}
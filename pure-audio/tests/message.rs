@@ -0,0 +1,142 @@
+use pure_audio::{
+    BlockAdapter, Command, CommandQueue, CustomMessage, EffectAudioData, Event, EventKind, InstrumentAudioData,
+    InstrumentSliceData, IntoProcessor, Messages, Notification, NotificationQueue, OutputBuffer, OutputSlices,
+    Processor, SampleAccurate, DEFAULT_COMMAND_QUEUE_CAPACITY,
+};
+
+// answers every command with a custom notification carrying its id, reports silence as a meter reading
+fn echo(EffectAudioData { mut messages, .. }: EffectAudioData<1, 1, 1, 64>) {
+    for command in messages.commands {
+        if let Command::Custom { id, value } = *command {
+            messages.notify(Notification::Custom { id, value: value * 2.0 });
+        }
+    }
+    messages.notify(Notification::Meter { output: 0, channel: 0, peak: 0.0 });
+}
+
+// counts the commands in the output, one notification per call
+fn count(
+    InstrumentAudioData {
+        outputs: OutputBuffer([[output]]),
+        mut messages,
+        ..
+    }: InstrumentAudioData<1, 1, 64>,
+) {
+    output.fill(messages.commands.len() as f32);
+    messages.notify(Notification::VoiceCount(messages.commands.len()));
+}
+
+fn count_slices(
+    InstrumentSliceData {
+        outputs: OutputSlices([[output]]),
+        mut messages,
+        ..
+    }: InstrumentSliceData<1, 1, 64>,
+) {
+    output.fill(messages.commands.len() as f32);
+    messages.notify(Notification::VoiceCount(messages.commands.len()));
+}
+
+#[test]
+fn commands_reach_the_function_and_notifications_are_queued() {
+    let mut processor = echo.into_processor(48000.0);
+    let mut notifications = NotificationQueue::default();
+    let commands = [Command::Reset, Command::Custom { id: 7, value: 1.5 }];
+    processor.process(&[[[0.0; 64]]], &mut [[[0.0; 64]]], &[], &[], &mut Messages::new(&commands, &mut notifications));
+
+    assert_eq!(
+        notifications.as_slice(),
+        [Notification::Custom { id: 7, value: 3.0 }, Notification::Meter { output: 0, channel: 0, peak: 0.0 }]
+    );
+}
+
+#[test]
+fn sample_accurate_delivers_commands_with_the_first_slice() {
+    let mut processor = SampleAccurate(count_slices).into_processor(48000.0);
+    let mut notifications = NotificationQueue::default();
+    let mut output = [[[0.0; 64]]];
+    // the event splits the block in two slices
+    let events = [Event::new(20, 0, EventKind::NoteOn { key: 60, velocity: 100 })];
+    processor.process(&[], &mut output, &[], &events, &mut Messages::new(&[Command::Reset], &mut notifications));
+
+    assert!(output[0][0][..20].iter().all(|sample| *sample == 1.0));
+    assert!(output[0][0][20..].iter().all(|sample| *sample == 0.0));
+    assert_eq!(notifications.as_slice(), [Notification::VoiceCount(1), Notification::VoiceCount(0)]);
+}
+
+#[test]
+fn adapter_delivers_commands_with_the_next_block() {
+    let mut adapter = BlockAdapter::new(count.into_processor(48000.0));
+    let mut notifications = NotificationQueue::default();
+    let mut output = vec![0.0; 128];
+
+    // the first call doesn't complete a block
+    let (first, second) = output.split_at_mut(32);
+    adapter.process(&[], &mut [[first]], &[], &[], &mut Messages::new(&[Command::Reset], &mut notifications));
+    assert!(notifications.is_empty());
+    adapter.process(&[], &mut [[second]], &[], &[], &mut Messages::new(&[], &mut notifications));
+
+    assert_eq!(notifications.as_slice(), [Notification::VoiceCount(1), Notification::VoiceCount(0)]);
+    // the output of the first block, delayed by the latency
+    assert!(output[64..].iter().all(|sample| *sample == 1.0));
+}
+
+#[test]
+fn notifications_beyond_the_capacity_are_dropped_and_counted() {
+    let mut processor = echo.into_processor(48000.0);
+    let mut notifications = NotificationQueue::new(1);
+    let commands = [Command::Custom { id: 1, value: 1.0 }, Command::Custom { id: 2, value: 1.0 }];
+    processor.process(&[[[0.0; 64]]], &mut [[[0.0; 64]]], &[], &[], &mut Messages::new(&commands, &mut notifications));
+
+    assert_eq!(notifications.as_slice(), [Notification::Custom { id: 1, value: 2.0 }]);
+    assert_eq!(notifications.dropped_count(), 2);
+
+    // draining makes room again, the count is kept
+    assert_eq!(notifications.drain().count(), 1);
+    assert!(notifications.push(Notification::VoiceCount(3)));
+    assert_eq!(notifications.dropped_count(), 2);
+}
+
+#[test]
+fn commands_beyond_the_capacity_are_dropped_and_counted() {
+    let mut commands = CommandQueue::default();
+    for _ in 0..DEFAULT_COMMAND_QUEUE_CAPACITY {
+        assert!(commands.push(Command::Reset));
+    }
+    assert!(!commands.push(Command::Custom { id: 1, value: 1.0 }));
+    assert_eq!(commands.len(), DEFAULT_COMMAND_QUEUE_CAPACITY);
+    assert_eq!(commands.dropped_count(), 1);
+}
+
+#[derive(Debug, PartialEq)]
+enum Transport {
+    Play,
+    Seek(f64),
+}
+
+impl CustomMessage for Transport {
+    fn into_custom(self) -> (u32, f64) {
+        match self {
+            Transport::Play => (1, 0.0),
+            Transport::Seek(seconds) => (2, seconds),
+        }
+    }
+
+    fn from_custom(id: u32, value: f64) -> Option<Self> {
+        match id {
+            1 => Some(Transport::Play),
+            2 => Some(Transport::Seek(value)),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn application_messages_travel_as_custom_messages() {
+    assert_eq!(Command::custom(Transport::Seek(1.5)), Command::Custom { id: 2, value: 1.5 });
+    assert_eq!(Command::custom(Transport::Play).to_custom(), Some(Transport::Play));
+    assert_eq!(Notification::custom(Transport::Seek(0.5)).to_custom(), Some(Transport::Seek(0.5)));
+    // other variants and ids aren't the application's
+    assert_eq!(Command::Reset.to_custom::<Transport>(), None);
+    assert_eq!(Notification::Custom { id: 7, value: 0.0 }.to_custom::<Transport>(), None);
+}
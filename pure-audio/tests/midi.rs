@@ -0,0 +1,103 @@
+use pure_audio::{midi::{encode, MidiMessage, MidiParser}, Event, EventKind};
+
+fn parse(bytes: &[u8]) -> Vec<Event> {
+    MidiParser::new().parse(bytes, 0).collect()
+}
+
+#[test]
+fn running_status() {
+    let events = parse(&[0x91, 60, 100, 64, 90, 60, 0]);
+    assert_eq!(events, [
+        Event::new(0, 1, EventKind::NoteOn { key: 60, velocity: 100 }),
+        Event::new(0, 1, EventKind::NoteOn { key: 64, velocity: 90 }),
+        Event::new(0, 1, EventKind::NoteOff { key: 60, velocity: 64 }),
+    ]);
+}
+
+#[test]
+fn realtime_bytes_are_transparent() {
+    let events = parse(&[0xE0, 0xF8, 0x00, 0xFE, 0x40, 0xF8]);
+    assert_eq!(events, [Event::new(0, 0, EventKind::PitchBend { value: EventKind::PITCH_BEND_CENTER })]);
+}
+
+#[test]
+fn sysex_is_skipped() {
+    let events = parse(&[0xC2, 5, 0xF0, 0x7E, 0x7F, 0xF8, 0x09, 0xF7, 7, 0xB0, 7, 127]);
+    assert_eq!(events, [
+        Event::new(0, 2, EventKind::ProgramChange { program: 5 }),
+        // data byte 7 is dropped because SysEx cancels running status
+        Event::new(0, 0, EventKind::ControlChange { controller: 7, value: 127 }),
+    ]);
+}
+
+#[test]
+fn state_persists_between_chunks() {
+    let mut parser = MidiParser::new();
+    assert_eq!(parser.parse(&[0x80, 60], 0).count(), 0);
+    assert_eq!(
+        parser.parse(&[10], 5).collect::<Vec<_>>(),
+        [Event::new(5, 0, EventKind::NoteOff { key: 60, velocity: 10 })]
+    );
+}
+
+#[test]
+fn encode_round_trip() {
+    let events = [
+        Event::new(0, 0, EventKind::NoteOn { key: 60, velocity: 100 }),
+        Event::new(0, 3, EventKind::NoteOff { key: 60, velocity: 20 }),
+        Event::new(0, 4, EventKind::PolyphonicKeyPressure { key: 61, pressure: 30 }),
+        Event::new(0, 5, EventKind::ControlChange { controller: 64, value: 127 }),
+        Event::new(0, 6, EventKind::ProgramChange { program: 12 }),
+        Event::new(0, 7, EventKind::ChannelPressure { pressure: 40 }),
+        Event::new(0, 15, EventKind::PitchBend { value: 16383 }),
+    ];
+    let mut bytes = vec![];
+    let mut buffer = [0; 3];
+    for event in &events {
+        bytes.extend_from_slice(encode(event, &mut buffer));
+    }
+    assert_eq!(parse(&bytes), events);
+}
+
+// SysEx messages borrow the buffer of the parser, these are copies
+fn messages(parser: &mut MidiParser, bytes: &[u8]) -> (Vec<Event>, Vec<Vec<u8>>) {
+    let (mut events, mut sysex) = (vec![], vec![]);
+    parser.parse_messages(bytes, 0, |message| match message {
+        MidiMessage::Event(event) => events.push(event),
+        MidiMessage::SysEx(bytes) => sysex.push(bytes.to_vec()),
+    });
+    (events, sysex)
+}
+
+#[test]
+fn sysex_messages_are_complete() {
+    let (events, sysex) = messages(&mut MidiParser::with_sysex(8), &[0xF0, 0x7E, 0xF8, 0x7F, 0x09, 0xF7, 0x90, 60, 100]);
+    // without the realtime byte
+    assert_eq!(sysex, [[0xF0, 0x7E, 0x7F, 0x09, 0xF7]]);
+    assert_eq!(events, [Event::new(0, 0, EventKind::NoteOn { key: 60, velocity: 100 })]);
+}
+
+#[test]
+fn split_sysex_is_reassembled() {
+    let mut parser = MidiParser::with_sysex(8);
+    assert_eq!(parser.push_message(0xF0, 0), None);
+    assert_eq!(messages(&mut parser, &[0x43, 0x10]), (vec![], vec![]));
+    assert_eq!(parser.push_message(0x4C, 0), None);
+    assert_eq!(parser.push_message(0xF7, 0), Some(MidiMessage::SysEx(&[0xF0, 0x43, 0x10, 0x4C, 0xF7])));
+}
+
+#[test]
+fn interrupted_and_oversized_sysex_is_dropped() {
+    let mut parser = MidiParser::with_sysex(4);
+    // a status byte ends the SysEx message, the note still counts
+    let (events, sysex) = messages(&mut parser, &[0xF0, 0x7E, 0x90, 60, 100, 0xF7]);
+    assert_eq!(events, [Event::new(0, 0, EventKind::NoteOn { key: 60, velocity: 100 })]);
+    assert!(sysex.is_empty());
+
+    // 5 bytes with F0 and F7
+    assert_eq!(messages(&mut parser, &[0xF0, 1, 2, 3, 0xF7]), (vec![], vec![]));
+    assert_eq!(messages(&mut parser, &[0xF0, 1, 2, 0xF7]), (vec![], vec![vec![0xF0, 1, 2, 0xF7]]));
+
+    // parsers without a SysEx buffer skip SysEx
+    assert_eq!(messages(&mut MidiParser::new(), &[0xF0, 1, 0xF7]), (vec![], vec![]));
+}
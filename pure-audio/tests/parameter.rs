@@ -0,0 +1,109 @@
+use pure_audio::{
+    AudioRate, EffectAudioData, FromParameters, HasParameter, InputBuffer, IntoProcessor, Messages, NotificationQueue,
+    OutputBuffer, ParameterAutomationRate, ParameterDescriptor, Processor, ProcessorParameter,
+};
+
+#[derive(Copy, Clone)]
+struct Volume(f32);
+
+// declared a-rate, taken per sample as AudioRate
+impl ProcessorParameter for Volume {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::A,
+        default_value: 1.0,
+        max_value: 1.0,
+        min_value: 0.0,
+        name: "Volume",
+    };
+
+    fn from_parameter(value: f32) -> Self {
+        Volume(value)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Level(f32);
+
+// declared k-rate, taken per block as the plain value
+impl ProcessorParameter for Level {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::K,
+        default_value: 1.0,
+        max_value: 1.0,
+        min_value: 0.0,
+        name: "Level",
+    };
+
+    fn from_parameter(value: f32) -> Self {
+        Level(value)
+    }
+}
+
+fn per_sample(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData<1, 1, 1, 4>,
+    volume: AudioRate<Volume, 4>,
+) {
+    for ((input_sample, output_sample), Volume(volume)) in input.iter().zip(output).zip(volume.iter()) {
+        *output_sample = input_sample * volume;
+    }
+}
+
+fn per_block(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData<1, 1, 1, 4>,
+    Level(level): Level,
+) {
+    for (input_sample, output_sample) in input.iter().zip(output) {
+        *output_sample = input_sample * level;
+    }
+}
+
+fn is_a_rate<P: FromParameters<4>>() -> bool {
+    matches!(P::DESCRIPTOR.automation_rate, ParameterAutomationRate::A)
+}
+
+fn has_parameter<Params: HasParameter<P>, P>() -> bool {
+    Params::HAS_PARAMETER
+}
+
+#[test]
+fn parameters_register_their_declared_rate() {
+    assert!(is_a_rate::<AudioRate<Volume, 4>>());
+    assert!(!is_a_rate::<Level>());
+}
+
+#[test]
+fn parameters_of_processors_and_graphs_are_known() {
+    assert!(has_parameter::<(AudioRate<Volume, 4>, Level), Volume>());
+    assert!(!has_parameter::<(AudioRate<Volume, 4>,), Level>());
+    // graphs take the parameters of both of their processors
+    assert!(has_parameter::<((AudioRate<Volume, 4>,), (Level,)), Level>());
+    assert!(!has_parameter::<((), ()), Level>());
+}
+
+#[test]
+fn audio_rate_borrows_the_values_of_the_block() {
+    let parameters = [[0.0, 0.25, 0.5, 1.0]];
+    let volume = <AudioRate<Volume, 4> as FromParameters<4>>::from_parameters(&parameters, 0);
+    assert!(std::ptr::eq(&*volume, &parameters[0]));
+
+    let mut processor = per_sample.into_processor(48000.0);
+    let mut output = [[[0.0; 4]]];
+    processor.process(&[[[2.0; 4]]], &mut output, &parameters, &[], &mut Messages::new(&[], &mut NotificationQueue::default()));
+    assert_eq!(output, [[[0.0, 0.5, 1.0, 2.0]]]);
+}
+
+#[test]
+fn plain_parameters_take_the_first_value_of_the_block() {
+    let mut processor = per_block.into_processor(48000.0);
+    let mut output = [[[0.0; 4]]];
+    processor.process(&[[[2.0; 4]]], &mut output, &[[0.5, 0.0, 0.0, 0.0]], &[], &mut Messages::new(&[], &mut NotificationQueue::default()));
+    assert_eq!(output, [[[1.0; 4]]]);
+}
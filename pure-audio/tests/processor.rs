@@ -0,0 +1,141 @@
+use pure_audio::{
+    EffectAudioData, Event, EventKind, InputBuffer, InstrumentAudioData, IntoProcessor, Messages, NotificationQueue, OutputBuffer,
+    ParameterAutomationRate, ParameterDescriptor, Processor, ProcessorParameter,
+};
+
+const NAMES: [&str; 16] = [
+    "P1", "P2", "P3", "P4", "P5", "P6", "P7", "P8", "P9", "P10", "P11", "P12", "P13", "P14", "P15", "P16",
+];
+
+#[derive(Copy, Clone)]
+struct Param<const I: usize>(f32);
+
+impl<const I: usize> ProcessorParameter for Param<I> {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::K,
+        default_value: 0.0,
+        max_value: 100.0,
+        min_value: 0.0,
+        name: NAMES[I],
+    };
+
+    fn from_parameter(value: f32) -> Self {
+        Param(value)
+    }
+}
+
+fn copy(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData<1, 1, 1, 8>,
+) {
+    output.copy_from_slice(input);
+}
+
+// outputs the number of events
+fn count(
+    InstrumentAudioData {
+        events,
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: InstrumentAudioData<1, 1, 8>,
+) {
+    output.fill(events.len() as f32);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_all(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData<1, 1, 1, 8>,
+    Param(p1): Param<0>, Param(p2): Param<1>, Param(p3): Param<2>, Param(p4): Param<3>,
+    Param(p5): Param<4>, Param(p6): Param<5>, Param(p7): Param<6>, Param(p8): Param<7>,
+    Param(p9): Param<8>, Param(p10): Param<9>, Param(p11): Param<10>, Param(p12): Param<11>,
+    Param(p13): Param<12>, Param(p14): Param<13>, Param(p15): Param<14>, Param(p16): Param<15>,
+) {
+    let sum = p1 + p2 + p3 + p4 + p5 + p6 + p7 + p8 + p9 + p10 + p11 + p12 + p13 + p14 + p15 + p16;
+    for (input_sample, output_sample) in input.iter().zip(output.iter_mut()) {
+        *output_sample = input_sample + sum;
+    }
+}
+
+// the last parameter, weighted by the first
+#[allow(clippy::too_many_arguments)]
+fn last(
+    InstrumentAudioData {
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: InstrumentAudioData<1, 1, 8>,
+    Param(p1): Param<0>, _: Param<1>, _: Param<2>, _: Param<3>, _: Param<4>, _: Param<5>, _: Param<6>, _: Param<7>,
+    _: Param<8>, _: Param<9>, _: Param<10>, _: Param<11>, _: Param<12>, _: Param<13>, _: Param<14>,
+    Param(p16): Param<15>,
+) {
+    output.fill(p1 * p16);
+}
+
+fn descriptors<
+    const IS_INSTRUMENT: bool,
+    const NUM_INPUTS: usize,
+    const NUM_OUTPUTS: usize,
+    const NUM_CHANNELS: usize,
+    const BLOCK_SIZE: usize,
+    const NUM_PARAMS: usize,
+    Params,
+    S,
+    F: IntoProcessor<IS_INSTRUMENT, NUM_INPUTS, NUM_OUTPUTS, NUM_CHANNELS, BLOCK_SIZE, NUM_PARAMS, Params, S>,
+>(
+    _process: &F,
+) -> [ParameterDescriptor; NUM_PARAMS] {
+    F::get_parameter_descriptors()
+}
+
+// parameter i is i + 1 for the whole block
+fn parameters() -> [[f32; 8]; 16] {
+    std::array::from_fn(|i| [i as f32 + 1.0; 8])
+}
+
+#[test]
+fn effects_without_parameters() {
+    assert_eq!(descriptors(&copy).len(), 0);
+    let mut processor = copy.into_processor(48000.0);
+    let mut output = [[[0.0; 8]]];
+    processor.process(&[[[0.5; 8]]], &mut output, &[], &[], &mut Messages::new(&[], &mut NotificationQueue::default()));
+    assert_eq!(output, [[[0.5; 8]]]);
+}
+
+#[test]
+fn instruments_without_parameters() {
+    assert_eq!(descriptors(&count).len(), 0);
+    let mut processor = count.into_processor(48000.0);
+    let mut output = [[[0.0; 8]]];
+    let events = [
+        Event::new(0, 0, EventKind::NoteOn { key: 60, velocity: 100 }),
+        Event::new(3, 0, EventKind::NoteOff { key: 60, velocity: 0 }),
+    ];
+    processor.process(&[], &mut output, &[], &events, &mut Messages::new(&[], &mut NotificationQueue::default()));
+    assert_eq!(output, [[[2.0; 8]]]);
+}
+
+#[test]
+fn effects_with_sixteen_parameters() {
+    assert_eq!(descriptors(&add_all).map(|descriptor| descriptor.name), NAMES);
+
+    let mut processor = add_all.into_processor(48000.0);
+    let mut output = [[[0.0; 8]]];
+    processor.process(&[[[0.5; 8]]], &mut output, &parameters(), &[], &mut Messages::new(&[], &mut NotificationQueue::default()));
+    // 1 + 2 + ... + 16
+    assert_eq!(output, [[[136.5; 8]]]);
+}
+
+#[test]
+fn instruments_with_sixteen_parameters() {
+    assert_eq!(descriptors(&last).map(|descriptor| descriptor.name), NAMES);
+    let mut processor = last.into_processor(48000.0);
+    let mut output = [[[0.0; 8]]];
+    processor.process(&[], &mut output, &parameters(), &[], &mut Messages::new(&[], &mut NotificationQueue::default()));
+    assert_eq!(output, [[[16.0; 8]]]);
+}
@@ -0,0 +1,70 @@
+use pure_audio::{
+    render, EffectAudioData, EventKind, InputBuffer, InstrumentAudioData, OutputBuffer, ParameterAutomationRate,
+    ParameterChange, ParameterDescriptor, ProcessorParameter, TimedEvent,
+};
+
+#[derive(Copy, Clone)]
+struct Scale(f32);
+
+impl ProcessorParameter for Scale {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::K,
+        default_value: 1.0,
+        max_value: 4.0,
+        min_value: 0.0,
+        name: "Scale",
+    };
+
+    fn from_parameter(value: f32) -> Self {
+        Scale(value)
+    }
+}
+
+fn scale(
+    EffectAudioData {
+        inputs: InputBuffer([[input]]),
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: EffectAudioData<1, 1, 1, 64>,
+    Scale(scale): Scale,
+) {
+    for (input_sample, output_sample) in input.iter().zip(output) {
+        *output_sample = input_sample * scale;
+    }
+}
+
+// marks the offset of every event within its block with its key
+fn mark(
+    InstrumentAudioData {
+        events,
+        outputs: OutputBuffer([[output]]),
+        ..
+    }: InstrumentAudioData<1, 1, 64>,
+) {
+    output.fill(0.0);
+    for event in events {
+        if let EventKind::NoteOn { key, .. } = event.kind {
+            output[event.offset] = key as f32;
+        }
+    }
+}
+
+#[test]
+fn events_are_delivered_at_their_offset_within_the_block() {
+    let note_on = |frame, key| TimedEvent::new(frame, 0, EventKind::NoteOn { key, velocity: 100 });
+    // unsorted, the last one past the rendered frames
+    let events = [note_on(130, 2), note_on(5, 1), note_on(191, 3), note_on(200, 4)];
+    let [[output]] = render(mark, 48000.0, 192, &[], &[], &events);
+
+    let marked: Vec<_> =
+        output.iter().enumerate().filter(|(_, key)| **key != 0.0).map(|(frame, key)| (frame, *key)).collect();
+    assert_eq!(marked, [(5, 1.0), (130, 2.0), (191, 3.0)]);
+}
+
+#[test]
+#[should_panic(expected = "parameter change at frame 300 sets parameter 1 of 1")]
+fn parameter_changes_must_name_a_parameter() {
+    // checked up front, even when the change falls after the rendered frames
+    let parameters = [ParameterChange { frame: 300, index: 1, value: 2.0 }];
+    render(scale, 48000.0, 256, &[[&[1.0; 256]]], &parameters, &[]);
+}
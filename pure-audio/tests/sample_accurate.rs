@@ -0,0 +1,79 @@
+use pure_audio::{
+    render, EffectSliceData, EventKind, InputSlices, InstrumentSliceData, OutputSlices,
+    ParameterAutomationRate, ParameterChange, ParameterDescriptor, ProcessorParameter, SampleAccurate, TimedEvent,
+};
+
+#[derive(Copy, Clone)]
+struct Scale(f32);
+
+impl ProcessorParameter for Scale {
+    const DESCRIPTOR: ParameterDescriptor = ParameterDescriptor {
+        automation_rate: ParameterAutomationRate::K,
+        default_value: 1.0,
+        max_value: 4.0,
+        min_value: 0.0,
+        name: "Scale",
+    };
+
+    fn from_parameter(value: f32) -> Self {
+        Scale(value)
+    }
+}
+
+fn scale(
+    EffectSliceData {
+        inputs: InputSlices([[input]]),
+        outputs: OutputSlices([[output]]),
+        ..
+    }: EffectSliceData,
+    Scale(scale): Scale,
+) {
+    for (input_sample, output_sample) in input.iter().zip(output.iter_mut()) {
+        *output_sample = input_sample * scale;
+    }
+}
+
+// outputs 1 while a note is held
+fn gate(
+    InstrumentSliceData {
+        events,
+        outputs: OutputSlices([[output]]),
+        state: held,
+        ..
+    }: InstrumentSliceData<1, 1, 128, bool>,
+) {
+    for event in events {
+        assert_eq!(event.offset, 0);
+        match event.kind {
+            EventKind::NoteOn { .. } => *held = true,
+            EventKind::NoteOff { .. } => *held = false,
+            _ => {}
+        }
+    }
+    output.fill(if *held { 1.0 } else { 0.0 });
+}
+
+#[test]
+fn parameter_jumps_land_on_their_frame() {
+    let input = vec![1.0; 256];
+    let parameters = [
+        ParameterChange { frame: 37, index: 0, value: 2.0 },
+        ParameterChange { frame: 200, index: 0, value: 0.5 },
+    ];
+    let [[output]] = render(SampleAccurate(scale), 48000.0, input.len(), &[[&input]], &parameters, &[]);
+    assert!(output[..37].iter().all(|sample| *sample == 1.0));
+    assert!(output[37..200].iter().all(|sample| *sample == 2.0));
+    assert!(output[200..].iter().all(|sample| *sample == 0.5));
+}
+
+#[test]
+fn notes_land_on_their_frame() {
+    let events = [
+        TimedEvent::new(5, 0, EventKind::NoteOn { key: 60, velocity: 100 }),
+        TimedEvent::new(130, 0, EventKind::NoteOff { key: 60, velocity: 64 }),
+        TimedEvent::new(255, 0, EventKind::NoteOn { key: 62, velocity: 100 }),
+    ];
+    let [[output]] = render(SampleAccurate(gate), 48000.0, 256, &[], &[], &events);
+    let held = output.iter().enumerate().filter(|(_, sample)| **sample == 1.0).map(|(frame, _)| frame);
+    assert_eq!(held.collect::<Vec<_>>(), (5..130).chain([255]).collect::<Vec<_>>());
+}